@@ -1,20 +1,40 @@
 use anyhow::Result;
-use reqwest::{Client, Response};
+use reqwest::{header::COOKIE, Client, Response};
 use serde::Deserialize;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::cookies::{parse_cookies, CookieItem};
 use crate::error::BiliError;
+use crate::jar::SharedCookieStore;
+use crate::sign::{self, sign_form};
+use crate::wbi;
 
 // API 路径
 const QR_CODE_GENERATE_URL: &str = "https://passport.bilibili.com/x/passport-login/web/qrcode/generate";
 const QR_CODE_POLL_URL: &str = "https://passport.bilibili.com/x/passport-login/web/qrcode/poll";
 const USER_INFO_URL: &str = "https://api.bilibili.com/x/web-interface/nav";
+const TV_QR_CODE_AUTH_CODE_URL: &str = "https://passport.bilibili.com/x/passport-tv-login/qrcode/auth_code";
+const TV_QR_CODE_POLL_URL: &str = "https://passport.bilibili.com/x/passport-tv-login/qrcode/poll";
+const FINGER_SPI_URL: &str = "https://api.bilibili.com/x/frontend/finger/spi";
+const EXCLIMB_WUZHI_URL: &str = "https://api.bilibili.com/x/internal/gaia-gateway/ExClimbWuzhi";
+const GEN_WEB_TICKET_URL: &str = "https://api.bilibili.com/bapis/bilibili.api.ticket.v1.Ticket/GenWebTicket";
+const LOGOUT_URL: &str = "https://passport.bilibili.com/x/passport-login/web/login/exit/v2";
+const COOKIE_INFO_URL: &str = "https://passport.bilibili.com/x/passport-login/web/cookie/info";
+const COOKIE_REFRESH_URL: &str = "https://passport.bilibili.com/x/passport-login/web/cookie/refresh";
+const CONFIRM_REFRESH_URL: &str = "https://passport.bilibili.com/x/passport-login/web/confirm/refresh";
+const CORRESPOND_URL: &str = "https://www.bilibili.com/correspond/1";
 
-/// 创建HTTP客户端，带有cookie jar，并启用cookie存储
+/// 创建HTTP客户端，使用独立的Cookie存储
 pub fn create_client() -> Result<Client> {
+    create_client_with_store(Arc::new(SharedCookieStore::new()))
+}
+
+/// 创建使用指定Cookie存储的HTTP客户端，请求结束后可从 `store` 中枚举收到的Cookie
+pub fn create_client_with_store(store: Arc<SharedCookieStore>) -> Result<Client> {
     let client = Client::builder()
         .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .cookie_store(true)
+        .cookie_provider(store)
         .build()?;
     
     Ok(client)
@@ -33,7 +53,6 @@ pub fn get_timestamp() -> u64 {
 pub struct QrCodeGenerateResponse {
     pub code: i32,
     pub message: String,
-    #[allow(dead_code)]
     pub ttl: i32,
     pub data: Option<QrCodeGenerateData>,
 }
@@ -49,39 +68,176 @@ pub struct QrCodeGenerateData {
 pub struct QrCodePollResponse {
     pub code: i32,
     pub message: String,
-    #[allow(dead_code)]
     pub ttl: i32,
     pub data: Option<QrCodePollData>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct QrCodePollData {
-    #[allow(dead_code)]
+    /// 扫码成功后的跨域登录URL，查询参数中携带登录Cookies
     pub url: String,
     pub refresh_token: String,
-    #[allow(dead_code)]
     pub timestamp: u64,
     pub code: i32,
-    #[allow(dead_code)]
     pub message: String,
 }
 
+/// TV端二维码生成响应
+#[derive(Debug, Deserialize)]
+pub struct TvQrCodeResponse {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<TvQrCodeData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TvQrCodeData {
+    pub url: String,
+    pub auth_code: String,
+}
+
+/// TV端二维码轮询响应，未登录成功时 `data` 为空，状态由 `code` 表示
+#[derive(Debug, Deserialize)]
+pub struct TvQrCodePollResponse {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<TvLoginData>,
+}
+
+/// TV端登录成功后返回的令牌和Cookie信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct TvLoginData {
+    pub mid: u64,
+    pub access_token: String,
+    pub refresh_token: String,
+    /// access_token的有效期 (秒)
+    pub expires_in: i64,
+    #[serde(default)]
+    pub cookie_info: Option<TvCookieInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TvCookieInfo {
+    #[serde(default)]
+    pub cookies: Vec<TvCookie>,
+    #[serde(default)]
+    pub domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TvCookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub http_only: i32,
+    /// 过期时间戳 (秒)
+    #[serde(default)]
+    pub expires: i64,
+    #[serde(default)]
+    pub secure: i32,
+}
+
+/// buvid获取响应
+#[derive(Debug, Deserialize)]
+pub struct FingerSpiResponse {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<FingerSpiData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FingerSpiData {
+    /// buvid3
+    pub b_3: String,
+    /// buvid4
+    pub b_4: String,
+}
+
+/// bili_ticket生成响应
+#[derive(Debug, Deserialize)]
+pub struct WebTicketResponse {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<WebTicketData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebTicketData {
+    pub ticket: String,
+    /// 生成时间戳 (秒)
+    pub created_at: i64,
+    /// 有效期 (秒)
+    pub ttl: i64,
+}
+
 /// 用户信息响应
 #[derive(Debug, Deserialize)]
 pub struct UserInfoResponse {
     pub code: i32,
     pub message: String,
-    #[allow(dead_code)]
     pub ttl: i32,
     pub data: Option<UserInfoData>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct UserInfoData {
     #[serde(rename = "isLogin")]
     pub is_login: bool,
+    #[serde(default)]
     pub mid: u64,
+    #[serde(default)]
     pub uname: String,
+    /// 部分情况下nav会返回当前会话的CSRF令牌，应与bili_jct一致
+    #[serde(default)]
+    pub csrf: Option<String>,
+    /// WBI签名所需的key (未登录时也会返回)
+    #[serde(default)]
+    pub wbi_img: Option<WbiImg>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WbiImg {
+    pub img_url: String,
+    pub sub_url: String,
+}
+
+/// Cookie刷新检查响应
+#[derive(Debug, Deserialize)]
+pub struct CookieInfoResponse {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<CookieInfoData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CookieInfoData {
+    /// 是否需要刷新
+    pub refresh: bool,
+    /// 服务器时间戳（毫秒），用于生成correspondPath
+    pub timestamp: i64,
+}
+
+/// Cookie刷新响应
+#[derive(Debug, Deserialize)]
+pub struct CookieRefreshResponse {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<CookieRefreshData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CookieRefreshData {
+    pub status: i32,
+    pub message: String,
+    pub refresh_token: String,
+}
+
+/// 只关心状态码的响应 (data可能为空)
+#[derive(Debug, Deserialize)]
+pub struct BasicResponse {
+    pub code: i32,
+    #[serde(default)]
+    pub message: String,
 }
 
 /// 处理B站API响应的通用逻辑
@@ -164,6 +320,86 @@ impl ApiResponse for UserInfoResponse {
     }
 }
 
+impl ApiResponse for CookieInfoResponse {
+    type Data = CookieInfoData;
+    
+    fn get_code(&self) -> i32 {
+        self.code
+    }
+    
+    fn get_message(&self) -> String {
+        self.message.clone()
+    }
+    
+    fn get_data(&self) -> Option<Self::Data> {
+        self.data.clone()
+    }
+}
+
+impl ApiResponse for CookieRefreshResponse {
+    type Data = CookieRefreshData;
+    
+    fn get_code(&self) -> i32 {
+        self.code
+    }
+    
+    fn get_message(&self) -> String {
+        self.message.clone()
+    }
+    
+    fn get_data(&self) -> Option<Self::Data> {
+        self.data.clone()
+    }
+}
+
+impl ApiResponse for TvQrCodeResponse {
+    type Data = TvQrCodeData;
+    
+    fn get_code(&self) -> i32 {
+        self.code
+    }
+    
+    fn get_message(&self) -> String {
+        self.message.clone()
+    }
+    
+    fn get_data(&self) -> Option<Self::Data> {
+        self.data.clone()
+    }
+}
+
+impl ApiResponse for FingerSpiResponse {
+    type Data = FingerSpiData;
+    
+    fn get_code(&self) -> i32 {
+        self.code
+    }
+    
+    fn get_message(&self) -> String {
+        self.message.clone()
+    }
+    
+    fn get_data(&self) -> Option<Self::Data> {
+        self.data.clone()
+    }
+}
+
+impl ApiResponse for WebTicketResponse {
+    type Data = WebTicketData;
+    
+    fn get_code(&self) -> i32 {
+        self.code
+    }
+    
+    fn get_message(&self) -> String {
+        self.message.clone()
+    }
+    
+    fn get_data(&self) -> Option<Self::Data> {
+        self.data.clone()
+    }
+}
+
 // 添加Clone特性用于ApiResponse特性实现
 impl Clone for QrCodeGenerateData {
     fn clone(&self) -> Self {
@@ -192,6 +428,8 @@ impl Clone for UserInfoData {
             is_login: self.is_login,
             mid: self.mid,
             uname: self.uname.clone(),
+            csrf: self.csrf.clone(),
+            wbi_img: self.wbi_img.clone(),
         }
     }
 }
@@ -214,12 +452,178 @@ pub async fn poll_qrcode(client: &Client, qrcode_key: &str) -> Result<QrCodePoll
     handle_api_response::<QrCodePollResponse, _>(response, "无返回数据").await
 }
 
+/// 生成TV端登录二维码
+pub async fn generate_tv_qrcode(client: &Client) -> Result<TvQrCodeData> {
+    let ts = (get_timestamp() / 1000).to_string();
+    let form = sign_form(&[("local_id", "0"), ("ts", ts.as_str())], &sign::TV);
+    
+    let response = client.post(TV_QR_CODE_AUTH_CODE_URL).form(&form).send().await?;
+    handle_api_response::<TvQrCodeResponse, _>(response, "无返回数据").await
+}
+
+/// 轮询TV端二维码状态，返回原始响应以便根据状态码判断
+pub async fn poll_tv_qrcode(client: &Client, auth_code: &str) -> Result<TvQrCodePollResponse> {
+    let ts = (get_timestamp() / 1000).to_string();
+    let form = sign_form(&[("auth_code", auth_code), ("local_id", "0"), ("ts", ts.as_str())], &sign::TV);
+    
+    let response = client.post(TV_QR_CODE_POLL_URL).form(&form).send().await?;
+    check_response(&response).await?;
+    Ok(response.json().await?)
+}
+
 /// 获取用户信息
 pub async fn get_user_info(client: &Client) -> Result<UserInfoData> {
     let response = client.get(USER_INFO_URL).send().await?;
     handle_api_response::<UserInfoResponse, _>(response, "无返回数据").await
 }
 
+/// 使用指定的Cookies获取用户信息
+pub async fn get_user_info_with_cookies(client: &Client, cookie_header: &str) -> Result<UserInfoData> {
+    let response = client.get(USER_INFO_URL).header(COOKIE, cookie_header).send().await?;
+    handle_api_response::<UserInfoResponse, _>(response, "无返回数据").await
+}
+
+/// 使用指定的Cookies请求nav接口，返回原始响应 (未登录时不视为错误)
+pub async fn get_nav_response(client: &Client, cookie_header: &str) -> Result<UserInfoResponse> {
+    let response = client.get(USER_INFO_URL).header(COOKIE, cookie_header).send().await?;
+    check_response(&response).await?;
+    Ok(response.json().await?)
+}
+
+/// 使用指定的Cookies请求需要WBI签名的GET接口，返回响应中的JSON
+pub async fn get_wbi_signed<K, V>(
+    client: &Client,
+    url: &str,
+    params: &[(K, V)],
+    cookie_header: &str,
+) -> Result<serde_json::Value>
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let query = wbi::sign_params(client, cookie_header, params).await?;
+    let response = client
+        .get(format!("{}?{}", url, query))
+        .header(COOKIE, cookie_header)
+        .send()
+        .await?;
+    check_response(&response).await?;
+    Ok(response.json().await?)
+}
+
+/// 获取buvid3和buvid4
+pub async fn get_finger_spi(client: &Client) -> Result<FingerSpiData> {
+    let response = client.get(FINGER_SPI_URL).send().await?;
+    handle_api_response::<FingerSpiResponse, _>(response, "无返回数据").await
+}
+
+/// 上传浏览器指纹以激活buvid，`payload` 为指纹信息的JSON字符串
+pub async fn activate_buvid(client: &Client, cookie_header: &str, payload: &str) -> Result<()> {
+    let response = client
+        .post(EXCLIMB_WUZHI_URL)
+        .header(COOKIE, cookie_header)
+        .json(&serde_json::json!({ "payload": payload }))
+        .send()
+        .await?;
+    handle_basic_response(response).await
+}
+
+/// 生成bili_ticket，`hexsign` 为以固定key对 `ts{ts}` 计算的HMAC-SHA256
+pub async fn gen_web_ticket(client: &Client, ts: i64, hexsign: &str, csrf: &str) -> Result<WebTicketData> {
+    let ts = ts.to_string();
+    let response = client
+        .post(GEN_WEB_TICKET_URL)
+        .query(&[
+            ("key_id", "ec02"),
+            ("hexsign", hexsign),
+            ("context[ts]", ts.as_str()),
+            ("csrf", csrf),
+        ])
+        .send()
+        .await?;
+    handle_api_response::<WebTicketResponse, _>(response, "无返回数据").await
+}
+
+/// 退出登录，使当前会话失效
+pub async fn logout(client: &Client, cookie_header: &str, csrf: &str) -> Result<()> {
+    let response = client
+        .post(LOGOUT_URL)
+        .header(COOKIE, cookie_header)
+        .form(&[("biliCSRF", csrf)])
+        .send()
+        .await?;
+    handle_basic_response(response).await
+}
+
+/// 检查是否需要刷新Cookie
+pub async fn get_cookie_info(client: &Client, cookie_header: &str, csrf: &str) -> Result<CookieInfoData> {
+    let response = client
+        .get(COOKIE_INFO_URL)
+        .query(&[("csrf", csrf)])
+        .header(COOKIE, cookie_header)
+        .send()
+        .await?;
+    handle_api_response::<CookieInfoResponse, _>(response, "无返回数据").await
+}
+
+/// 获取correspond页面的HTML (其中包含refresh_csrf)
+pub async fn get_correspond_page(client: &Client, cookie_header: &str, correspond_path: &str) -> Result<String> {
+    let url = format!("{}/{}", CORRESPOND_URL, correspond_path);
+    let response = client.get(url).header(COOKIE, cookie_header).send().await?;
+    check_response(&response).await?;
+    Ok(response.text().await?)
+}
+
+/// 刷新Cookie，同时返回响应中Set-Cookie下发的新Cookie
+pub async fn refresh_cookie(
+    client: &Client,
+    cookie_header: &str,
+    csrf: &str,
+    refresh_csrf: &str,
+    refresh_token: &str,
+) -> Result<(CookieRefreshData, Vec<CookieItem>)> {
+    let response = client
+        .post(COOKIE_REFRESH_URL)
+        .header(COOKIE, cookie_header)
+        .form(&[
+            ("csrf", csrf),
+            ("refresh_csrf", refresh_csrf),
+            ("source", "main_web"),
+            ("refresh_token", refresh_token),
+        ])
+        .send()
+        .await?;
+    let cookies = parse_cookies(response.headers(), response.url());
+    let data = handle_api_response::<CookieRefreshResponse, _>(response, "无返回数据").await?;
+    Ok((data, cookies))
+}
+
+/// 确认刷新，使旧的refresh_token失效
+pub async fn confirm_refresh(
+    client: &Client,
+    cookie_header: &str,
+    csrf: &str,
+    old_refresh_token: &str,
+) -> Result<()> {
+    let response = client
+        .post(CONFIRM_REFRESH_URL)
+        .header(COOKIE, cookie_header)
+        .form(&[("csrf", csrf), ("refresh_token", old_refresh_token)])
+        .send()
+        .await?;
+    handle_basic_response(response).await
+}
+
+/// 处理只需检查状态码的响应
+async fn handle_basic_response(response: Response) -> Result<()> {
+    check_response(&response).await?;
+    let res_data: BasicResponse = response.json().await?;
+    match res_data.code {
+        0 => Ok(()),
+        code => Err(BiliError::ApiError(code, res_data.message).into()),
+    }
+}
+
 /// 检查响应状态
 async fn check_response(response: &Response) -> Result<()> {
     if response.status().is_success() {
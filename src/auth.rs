@@ -1,137 +1,173 @@
 use anyhow::Result;
-use colored::*;
-use image::{ImageBuffer, Luma};
-use qrcode::QrCode;
 use reqwest::Client;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
 
 use crate::api::{
-    create_client, generate_qrcode, get_user_info, poll_qrcode, QrCodePollData, UserInfoData,
+    create_client, create_client_with_store, generate_qrcode, generate_tv_qrcode, get_user_info, get_user_info_with_cookies,
+    poll_qrcode, poll_tv_qrcode, QrCodePollData, UserInfoData,
 };
+use crate::cookies::{cookies_to_header_string, from_tv_cookie_info, CookieItem};
 use crate::error::BiliError;
+use crate::jar::SharedCookieStore;
 
 /// 登录成功后的结果
 #[derive(Debug)]
 pub struct LoginResult {
     pub client: Client,
+    /// `client` 使用的Cookie存储，记录了登录过程中服务器下发的全部Cookie
+    pub cookie_store: Arc<SharedCookieStore>,
+    /// 扫码成功后返回的跨域登录URL，查询参数中携带登录Cookies
+    pub login_url: String,
     pub refresh_token: String,
     pub uid: u64,
     pub username: String,
 }
 
+/// TV端扫码登录成功后的结果，包含app端的access_key及网页端Cookies
+#[derive(Debug, Clone)]
+pub struct TvLoginResult {
+    pub uid: u64,
+    pub username: String,
+    /// 即app接口使用的 access_key
+    pub access_token: String,
+    pub refresh_token: String,
+    /// access_token的有效期 (秒)
+    pub expires_in: i64,
+    /// 由cookie_info转换而来的网页端Cookies
+    pub cookies: Vec<CookieItem>,
+}
+
+/// 扫码登录过程中的状态变化，通过回调通知调用方
+///
+/// 本库不在终端输出任何内容，也不写入文件；展示二维码 (终端、图片或网页) 由调用方负责。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginEvent {
+    /// 已生成二维码，调用方应将该URL编码为二维码展示给用户
+    QrCode(String),
+    /// 尚未扫描，每次轮询都会通知一次
+    Waiting,
+    /// 已扫描，等待App确认
+    Scanned,
+    /// 已确认登录，正在获取用户信息
+    Confirmed,
+    /// 未知的轮询状态码，将继续轮询
+    Unknown(i32),
+}
+
+/// 轮询间隔及最大轮询次数 (约3分钟，与二维码有效期相当)
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const MAX_POLL_ATTEMPTS: u32 = 90;
+
 // 二维码状态常量
 const QR_CODE_STATUS_SUCCESS: i32 = 0; // 成功 (已确认)
 const QR_CODE_STATUS_SCANNED: i32 = 86038; // 已扫描，待确认
 const QR_CODE_STATUS_EXPIRED: i32 = 86039; // 二维码已过期
 const QR_CODE_STATUS_NOT_SCANNED_YET: i32 = 86101; // 未扫描 (或还在初始化)
 
-// 二维码登录流程
-pub async fn login_with_qrcode() -> Result<LoginResult> {
-    let client = create_client()?;
+// TV端二维码状态常量
+const TV_QR_CODE_STATUS_SUCCESS: i32 = 0; // 成功
+const TV_QR_CODE_STATUS_EXPIRED: i32 = 86038; // 二维码已失效
+const TV_QR_CODE_STATUS_NOT_SCANNED_YET: i32 = 86039; // 尚未扫描
+const TV_QR_CODE_STATUS_SCANNED: i32 = 86090; // 已扫描，待确认
+
+/// 网页端扫码登录，登录过程中的状态通过 `on_event` 通知
+///
+/// ```no_run
+/// # async fn run() -> anyhow::Result<()> {
+/// use bilicookies_rs::auth::{login_with_qrcode, LoginEvent};
+///
+/// let login_result = login_with_qrcode(|event| {
+///     if let LoginEvent::QrCode(url) = event {
+///         println!("请扫码: {}", url);
+///     }
+/// }).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn login_with_qrcode(mut on_event: impl FnMut(LoginEvent)) -> Result<LoginResult> {
+    let cookie_store = Arc::new(SharedCookieStore::new());
+    let client = create_client_with_store(cookie_store.clone())?;
     let qr_data = generate_qrcode(&client).await?;
-    let code_for_image = QrCode::new(qr_data.url.as_bytes())?; // For image generation
-    let code_for_terminal = QrCode::new(qr_data.url.as_bytes())?; // For terminal rendering
-
-    // --- 保存二维码到文件 ---
-    let width = code_for_image.width();
-    if width == 0 {
-        println!("{}", "无法生成二维码图像：宽度为0".red());
-    } else {
-        let colors = code_for_image.to_colors(); // Vec<qrcode::Color>
-        let scale = 6u32;
-        let image_size = (width as u32) * scale;
-        let mut img_buf = ImageBuffer::new(image_size, image_size);
-
-        for y_qr in 0..width {
-            for x_qr in 0..width {
-                let module_index = y_qr * width + x_qr;
-                let pixel_color = match colors[module_index] {
-                    qrcode::Color::Dark => Luma([0u8]),
-                    qrcode::Color::Light => Luma([255u8]),
-                };
-
-                for y_offset in 0..scale {
-                    for x_offset in 0..scale {
-                        img_buf.put_pixel(
-                            (x_qr as u32) * scale + x_offset,
-                            (y_qr as u32) * scale + y_offset,
-                            pixel_color,
-                        );
-                    }
-                }
-            }
-        }
-        match img_buf.save("qrcode.png") {
-            Ok(_) => println!("二维码图片已保存为 qrcode.png, 您也可以扫描此文件。"),
-            Err(e) => println!(
-                "无法保存二维码图片到文件: {}. 请扫描下方终端二维码。",
-                e.to_string().red()
-            ),
-        }
-    }
-    // --- 结束保存二维码到文件 ---
-
-    // --- 终端二维码 ---
-    let terminal_qr_string = code_for_terminal
-        .render::<qrcode::render::unicode::Dense1x2>()
-        .dark_color(qrcode::render::unicode::Dense1x2::Light)
-        .light_color(qrcode::render::unicode::Dense1x2::Dark)
-        .build();
-    println!("\n{}", terminal_qr_string);
-    println!(
-        "{}",
-        "请使用B站手机APP扫描上方二维码或 qrcode.png 文件并确认登录...".yellow()
-    );
-
-    let mut poll_attempts = 0;
-    let max_poll_attempts = 90;
-
-    loop {
-        if poll_attempts >= max_poll_attempts {
-            println!("\n{}", "✗ 轮询超时，二维码可能已过期或网络问题。".red());
-            return Err(BiliError::LoginError("二维码轮询超时或已过期".to_string()).into());
-        }
-        sleep(Duration::from_secs(2)).await;
-        poll_attempts += 1;
+    on_event(LoginEvent::QrCode(qr_data.url));
+
+    for _ in 0..MAX_POLL_ATTEMPTS {
+        sleep(POLL_INTERVAL).await;
 
         let poll_data: QrCodePollData = poll_qrcode(&client, &qr_data.qrcode_key).await?;
 
         match poll_data.code {
             QR_CODE_STATUS_SUCCESS => {
-                println!("\n{}", "✓ 扫码成功!".green());
-                println!("正在获取用户信息...");
-
-                let refresh_token = poll_data.refresh_token;
+                on_event(LoginEvent::Confirmed);
 
                 let user_info: UserInfoData = get_user_info(&client).await?;
-                let uid = user_info.mid;
-                let username = user_info.uname;
-
                 return Ok(LoginResult {
                     client,
-                    refresh_token,
-                    uid,
-                    username,
+                    cookie_store,
+                    login_url: poll_data.url,
+                    refresh_token: poll_data.refresh_token,
+                    uid: user_info.mid,
+                    username: user_info.uname,
                 });
             }
-            QR_CODE_STATUS_NOT_SCANNED_YET => {
-                print!(".");
-                std::io::Write::flush(&mut std::io::stdout())?;
+            QR_CODE_STATUS_NOT_SCANNED_YET => on_event(LoginEvent::Waiting),
+            QR_CODE_STATUS_SCANNED => on_event(LoginEvent::Scanned),
+            QR_CODE_STATUS_EXPIRED => {
+                return Err(BiliError::LoginError("二维码已过期".to_string()).into());
             }
-            QR_CODE_STATUS_SCANNED => {
-                println!("\n{}", "已扫描，等待App确认...".yellow());
+            other_code => on_event(LoginEvent::Unknown(other_code)),
+        }
+    }
+    Err(BiliError::LoginError("二维码轮询超时或已过期".to_string()).into())
+}
+
+/// TV端扫码登录，使用appkey/appsec签名，可同时获得access_key和网页端Cookies
+///
+/// 登录过程中的状态通过 `on_event` 通知，同 [`login_with_qrcode`]。
+pub async fn login_with_tv_qrcode(mut on_event: impl FnMut(LoginEvent)) -> Result<TvLoginResult> {
+    let client = create_client()?;
+    let qr_data = generate_tv_qrcode(&client).await?;
+    on_event(LoginEvent::QrCode(qr_data.url));
+
+    for _ in 0..MAX_POLL_ATTEMPTS {
+        sleep(POLL_INTERVAL).await;
+
+        let poll_response = poll_tv_qrcode(&client, &qr_data.auth_code).await?;
+
+        match poll_response.code {
+            TV_QR_CODE_STATUS_SUCCESS => {
+                let login_data = poll_response.data.ok_or_else(|| {
+                    BiliError::LoginError("登录成功但未返回令牌信息".to_string())
+                })?;
+                on_event(LoginEvent::Confirmed);
+
+                let cookies = login_data.cookie_info.as_ref()
+                    .map(from_tv_cookie_info)
+                    .unwrap_or_default();
+                let username = get_user_info_with_cookies(&client, &cookies_to_header_string(&cookies))
+                    .await
+                    .map(|info| info.uname)
+                    .unwrap_or_default();
+
+                return Ok(TvLoginResult {
+                    uid: login_data.mid,
+                    username,
+                    access_token: login_data.access_token,
+                    refresh_token: login_data.refresh_token,
+                    expires_in: login_data.expires_in,
+                    cookies,
+                });
             }
-            QR_CODE_STATUS_EXPIRED => {
-                println!("\n{}", "✗ 二维码已过期".red());
+            TV_QR_CODE_STATUS_NOT_SCANNED_YET => on_event(LoginEvent::Waiting),
+            TV_QR_CODE_STATUS_SCANNED => on_event(LoginEvent::Scanned),
+            TV_QR_CODE_STATUS_EXPIRED => {
                 return Err(BiliError::LoginError("二维码已过期".to_string()).into());
             }
             other_code => {
-                println!(
-                    "\n{}",
-                    format!("未知轮询状态，代码: {}。将继续尝试...", other_code).yellow()
-                );
+                return Err(BiliError::ApiError(other_code, poll_response.message).into());
             }
         }
     }
+    Err(BiliError::LoginError("二维码轮询超时或已过期".to_string()).into())
 }
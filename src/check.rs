@@ -0,0 +1,94 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+
+use crate::api::{get_nav_response, get_wbi_signed};
+use crate::cookies::{cookies_to_header_string, find_cookie_value, CookieItem};
+
+/// 用于WBI签名校验的空间信息接口
+const SPACE_INFO_URL: &str = "https://api.bilibili.com/x/space/wbi/acc/info";
+
+/// 单个Cookie的有效期信息
+#[derive(Debug, Clone)]
+pub struct CookieLifetime {
+    pub name: String,
+    /// 过期时间，`None` 表示会话Cookie
+    pub expires: Option<DateTime<Utc>>,
+    /// 剩余有效时间，已过期时为负值
+    pub remaining: Option<Duration>,
+}
+
+impl CookieLifetime {
+    pub fn is_expired(&self) -> bool {
+        self.remaining.is_some_and(|r| r <= Duration::zero())
+    }
+}
+
+/// Cookie有效性检查结果
+#[derive(Debug, Clone)]
+pub struct CheckReport {
+    /// nav接口返回的状态码 (0为已登录，-101为未登录)
+    pub code: i32,
+    pub message: String,
+    pub is_login: bool,
+    pub uid: Option<u64>,
+    pub uname: Option<String>,
+    /// DedeUserID是否与nav返回的UID一致，缺少DedeUserID时为 `None`
+    pub uid_matches: Option<bool>,
+    /// bili_jct是否与服务器返回的csrf一致，服务器未返回csrf或缺少bili_jct时为 `None`
+    pub csrf_matches: Option<bool>,
+    pub lifetimes: Vec<CookieLifetime>,
+}
+
+impl CheckReport {
+    /// 会话有效: 已登录，且DedeUserID和bili_jct都没有与服务器不一致
+    pub fn is_valid(&self) -> bool {
+        self.is_login && self.uid_matches != Some(false) && self.csrf_matches != Some(false)
+    }
+}
+
+/// 计算每个Cookie的剩余有效期
+pub fn cookie_lifetimes(cookies: &[CookieItem], now: DateTime<Utc>) -> Vec<CookieLifetime> {
+    cookies.iter()
+        .map(|c| CookieLifetime {
+            name: c.name.clone(),
+            expires: c.expires,
+            remaining: c.expires.map(|e| e - now),
+        })
+        .collect()
+}
+
+/// 使用给定的Cookies请求nav接口，检查会话是否有效
+pub async fn check_cookies(client: &Client, cookies: &[CookieItem]) -> Result<CheckReport> {
+    let response = get_nav_response(client, &cookies_to_header_string(cookies)).await?;
+    let data = response.data.unwrap_or_default();
+    let is_login = response.code == 0 && data.is_login;
+
+    let uid = (data.mid > 0).then_some(data.mid);
+    let uid_matches = match (find_cookie_value(cookies, "DedeUserID"), uid) {
+        (Some(cookie_uid), Some(uid)) => Some(cookie_uid == uid.to_string()),
+        _ => None,
+    };
+    let csrf_matches = match (find_cookie_value(cookies, "bili_jct"), data.csrf.as_deref()) {
+        (Some(bili_jct), Some(csrf)) if !csrf.is_empty() => Some(bili_jct == csrf),
+        _ => None,
+    };
+
+    Ok(CheckReport {
+        code: response.code,
+        message: response.message,
+        is_login,
+        uid,
+        uname: (!data.uname.is_empty()).then_some(data.uname),
+        uid_matches,
+        csrf_matches,
+        lifetimes: cookie_lifetimes(cookies, Utc::now()),
+    })
+}
+
+/// 使用给定的Cookies以WBI签名请求空间信息接口，返回接口状态码 (0为成功，-352为触发风控)
+pub async fn check_wbi(client: &Client, cookies: &[CookieItem], mid: u64) -> Result<i32> {
+    let mid = mid.to_string();
+    let body = get_wbi_signed(client, SPACE_INFO_URL, &[("mid", mid.as_str())], &cookies_to_header_string(cookies)).await?;
+    Ok(body.get("code").and_then(|c| c.as_i64()).unwrap_or(-1) as i32)
+}
@@ -0,0 +1,214 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use sha1::Sha1;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cookies::{CookieItem, CookieSource, Priority, SameSite};
+use crate::error::BiliError;
+use crate::jar::is_bilibili_domain;
+
+/// Chromium配置文件目录中Cookie数据库的位置，新版本位于 `Network` 子目录
+const COOKIES_DB_CANDIDATES: [&str; 2] = ["Network/Cookies", "Cookies"];
+
+/// 未使用系统密钥环时，Linux版Chromium用于派生密钥的固定口令及盐
+const LINUX_PASSWORD: &[u8] = b"peanuts";
+const LINUX_SALT: &[u8] = b"saltysalt";
+
+/// AES-128-CBC的初始向量: 16个空格
+const LINUX_IV: [u8; 16] = [b' '; 16];
+
+/// 从该数据库版本起，解密后的值以host_key的SHA-256开头
+const DOMAIN_HASH_DB_VERSION: i64 = 24;
+
+/// Chromium时间戳 (1601-01-01起的微秒数) 与Unix时间戳之间相差的秒数
+const WINDOWS_EPOCH_OFFSET_SECS: i64 = 11_644_473_600;
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+/// 读取Linux版Chromium (及Chrome等衍生浏览器) 的Cookies数据库，只保留B站的Cookie
+///
+/// `path` 可以是数据库文件本身，也可以是配置文件目录 (如 `~/.config/chromium/Default`)。
+/// 只支持 `v10` 加密，即未使用系统密钥环 (`--password-store=basic`) 时以 "peanuts" 派生的固定密钥。
+/// 浏览器运行时会锁定数据库，因此先复制一份再读取。
+pub fn read_chromium_cookies(path: &Path) -> Result<Vec<CookieItem>> {
+    let db_path = resolve_db_path(path)?;
+    let snapshot = std::env::temp_dir().join(format!("bilicookies-rs-chromium-{}.sqlite", std::process::id()));
+    fs::copy(&db_path, &snapshot)?;
+    let result = Connection::open_with_flags(&snapshot, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(Into::into)
+        .and_then(|conn| read_cookies(&conn));
+    let _ = fs::remove_file(&snapshot);
+
+    let cookies = result?;
+    if cookies.is_empty() {
+        return Err(BiliError::CookieError("Chromium中没有B站的Cookie，请先在浏览器中登录".to_string()).into());
+    }
+    Ok(cookies)
+}
+
+fn resolve_db_path(path: &Path) -> Result<PathBuf> {
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    COOKIES_DB_CANDIDATES.iter()
+        .map(|candidate| path.join(candidate))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| BiliError::BrowserError(format!("在 {} 中找不到Chromium的Cookies数据库", path.display())).into())
+}
+
+fn read_cookies(conn: &Connection) -> Result<Vec<CookieItem>> {
+    let version: Option<String> = conn
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| row.get(0))
+        .optional()?;
+    let has_domain_hash = version
+        .and_then(|v| v.parse::<i64>().ok())
+        .is_some_and(|v| v >= DOMAIN_HASH_DB_VERSION);
+    let key = linux_key();
+
+    let mut statement = conn.prepare(
+        "SELECT host_key, name, value, encrypted_value, path, expires_utc, has_expires, is_secure, is_httponly,
+            samesite, priority, creation_utc, last_access_utc
+         FROM cookies WHERE host_key = 'bilibili.com' OR host_key LIKE '%.bilibili.com'",
+    )?;
+    let mut rows = statement.query([])?;
+    let mut cookies = Vec::new();
+    while let Some(row) = rows.next()? {
+        let host_key: String = row.get("host_key")?;
+        if !is_bilibili_domain(&host_key) {
+            continue;
+        }
+        let name: String = row.get("name")?;
+        let mut value: String = row.get("value")?;
+        let encrypted_value: Vec<u8> = row.get("encrypted_value")?;
+        if value.is_empty() && !encrypted_value.is_empty() {
+            value = decrypt_value(&encrypted_value, &key, has_domain_hash)
+                .map_err(|e| BiliError::BrowserError(format!("无法解密Cookie {}: {}", name, e)))?;
+        }
+        let has_expires: bool = row.get("has_expires")?;
+
+        cookies.push(CookieItem {
+            host_only: !host_key.starts_with('.'),
+            domain: host_key,
+            name,
+            value,
+            path: row.get("path")?,
+            expires: if has_expires { from_chromium_time(row.get("expires_utc")?) } else { None },
+            http_only: row.get("is_httponly")?,
+            secure: row.get("is_secure")?,
+            same_site: match row.get::<_, i64>("samesite")? {
+                0 => Some(SameSite::None),
+                1 => Some(SameSite::Lax),
+                2 => Some(SameSite::Strict),
+                _ => None,
+            },
+            partitioned: false,
+            priority: match row.get::<_, i64>("priority")? {
+                0 => Some(Priority::Low),
+                2 => Some(Priority::High),
+                _ => Some(Priority::Medium),
+            },
+            creation_time: from_chromium_time(row.get("creation_utc")?),
+            last_access: from_chromium_time(row.get("last_access_utc")?),
+            source: CookieSource::Imported,
+        });
+    }
+    Ok(cookies)
+}
+
+/// 以PBKDF2-HMAC-SHA1 (1次迭代) 派生的16字节AES密钥
+fn linux_key() -> [u8; 16] {
+    let mut key = [0u8; 16];
+    pbkdf2::pbkdf2_hmac::<Sha1>(LINUX_PASSWORD, LINUX_SALT, 1, &mut key);
+    key
+}
+
+fn decrypt_value(encrypted: &[u8], key: &[u8; 16], has_domain_hash: bool) -> Result<String, String> {
+    let ciphertext = match encrypted.split_at_checked(3) {
+        Some((b"v10", ciphertext)) => ciphertext,
+        Some((b"v11", _)) => {
+            return Err("该值由系统密钥环 (v11) 加密，暂不支持；请以 --password-store=basic 启动Chromium后重新登录".to_string());
+        }
+        _ => return Err("未知的加密格式".to_string()),
+    };
+    let mut buffer = ciphertext.to_vec();
+    let plaintext = Aes128CbcDec::new(key.into(), &LINUX_IV.into())
+        .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+        .map_err(|_| "密钥不匹配或数据已损坏".to_string())?;
+    let plaintext = if has_domain_hash {
+        plaintext.get(32..).ok_or_else(|| "解密结果过短".to_string())?
+    } else {
+        plaintext
+    };
+    String::from_utf8(plaintext.to_vec()).map_err(|e| e.to_string())
+}
+
+/// 0表示未设置
+fn from_chromium_time(micros: i64) -> Option<DateTime<Utc>> {
+    if micros <= 0 {
+        return None;
+    }
+    DateTime::from_timestamp_micros(micros - WINDOWS_EPOCH_OFFSET_SECS * 1_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+    use rusqlite::params;
+    use sha2::{Digest, Sha256};
+
+    const SCHEMA: &str = "CREATE TABLE meta (key LONGVARCHAR NOT NULL UNIQUE PRIMARY KEY, value LONGVARCHAR);
+        CREATE TABLE cookies (creation_utc INTEGER NOT NULL, host_key TEXT NOT NULL, top_frame_site_key TEXT NOT NULL DEFAULT '',
+        name TEXT NOT NULL, value TEXT NOT NULL, encrypted_value BLOB NOT NULL, path TEXT NOT NULL, expires_utc INTEGER NOT NULL,
+        is_secure INTEGER NOT NULL, is_httponly INTEGER NOT NULL, last_access_utc INTEGER NOT NULL, has_expires INTEGER NOT NULL,
+        is_persistent INTEGER NOT NULL, priority INTEGER NOT NULL, samesite INTEGER NOT NULL);
+        INSERT INTO meta VALUES ('version', '24');";
+
+    fn encrypt(host_key: &str, value: &str) -> Vec<u8> {
+        let mut plaintext = Sha256::digest(host_key.as_bytes()).to_vec();
+        plaintext.extend_from_slice(value.as_bytes());
+        let message_len = plaintext.len();
+        plaintext.resize(message_len + 16, 0);
+        let ciphertext = cbc::Encryptor::<aes::Aes128>::new(&linux_key().into(), &LINUX_IV.into())
+            .encrypt_padded_mut::<Pkcs7>(&mut plaintext, message_len)
+            .unwrap();
+        [b"v10".as_slice(), ciphertext].concat()
+    }
+
+    #[test]
+    fn reads_and_decrypts_bilibili_rows() {
+        let dir = std::env::temp_dir().join(format!("bilicookies-rs-test-{}-chromium", std::process::id()));
+        fs::create_dir_all(dir.join("Network")).unwrap();
+        let path = dir.join("Network/Cookies");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        let insert = "INSERT INTO cookies (creation_utc, host_key, name, value, encrypted_value, path, expires_utc, is_secure,
+            is_httponly, last_access_utc, has_expires, is_persistent, priority, samesite)
+            VALUES (13370000000000000, ?1, ?2, ?3, ?4, '/', ?5, 1, ?6, 13370000000000000, ?7, ?7, 1, ?8)";
+        let rows = [
+            (".bilibili.com", "SESSDATA", "", encrypt(".bilibili.com", "abc%2C123"), 13400000000000000i64, true, true, 1),
+            ("www.bilibili.com", "b_lsid", "plain", Vec::new(), 0, false, false, -1),
+            (".example.com", "token", "", b"v11garbage".to_vec(), 13400000000000000, true, true, 0),
+        ];
+        for (host, name, value, encrypted, expires, http_only, persistent, same_site) in rows {
+            conn.execute(insert, params![host, name, value, encrypted, expires, http_only, persistent, same_site]).unwrap();
+        }
+        drop(conn);
+
+        let cookies = read_chromium_cookies(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(cookies.len(), 2);
+        let sessdata = &cookies[0];
+        assert_eq!((sessdata.name.as_str(), sessdata.value.as_str()), ("SESSDATA", "abc%2C123"));
+        assert!(!sessdata.host_only && sessdata.http_only && sessdata.secure);
+        assert_eq!(sessdata.same_site, Some(SameSite::Lax));
+        assert_eq!(sessdata.expires.unwrap().timestamp(), 13400000000 - WINDOWS_EPOCH_OFFSET_SECS);
+        let lsid = &cookies[1];
+        assert_eq!(lsid.value, "plain");
+        assert!(lsid.host_only && lsid.expires.is_none() && lsid.same_site.is_none());
+    }
+}
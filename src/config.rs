@@ -1,38 +1,267 @@
 use anyhow::Result;
 use directories::ProjectDirs;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fs,
     path::PathBuf,
 };
 
-use crate::cookies::CookieItem;
+use crate::auth::TvLoginResult;
+use crate::cookies::{merge_cookies, CookieItem, CookieSource};
 use crate::error::BiliError;
+use crate::ticket::BiliTicket;
 
-/// 应用配置
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Config {
-    pub user_id: Option<u64>,
-    pub username: Option<String>,
+/// app端 (TV扫码登录) 的令牌
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppToken {
+    /// 即app接口使用的 access_key
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AppToken {
+    /// 由TV扫码登录结果生成，过期时间从当前时间起算
+    pub fn from_tv_login(result: &TvLoginResult) -> Self {
+        Self {
+            access_token: result.access_token.clone(),
+            refresh_token: result.refresh_token.clone(),
+            expires_at: Utc::now() + chrono::Duration::seconds(result.expires_in),
+        }
+    }
+
+    /// 生成导出用的令牌信息，`expires_in` 为从 `now` 起的剩余秒数
+    pub fn to_export(&self, mid: u64, now: DateTime<Utc>) -> AppTokenExport {
+        AppTokenExport {
+            mid,
+            access_token: self.access_token.clone(),
+            refresh_token: self.refresh_token.clone(),
+            expires_in: (self.expires_at - now).num_seconds().max(0),
+            expires_at: self.expires_at,
+        }
+    }
+}
+
+/// app端令牌的导出形式
+///
+/// 令牌不是Cookie，单独保存为JSON文件，不会混入任何Cookie输出格式。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppTokenExport {
+    pub mid: u64,
+    pub access_token: String,
+    pub refresh_token: String,
+    /// 剩余有效期 (秒)
+    pub expires_in: i64,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 单个账号的登录信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub uid: u64,
+    pub username: String,
+    /// 便于记忆的别名，可代替UID选择账号
+    #[serde(default)]
+    pub alias: Option<String>,
+    #[serde(default)]
     pub refresh_token: Option<String>,
-    pub cookies: Option<Vec<CookieItem>>,
+    #[serde(default)]
+    pub cookies: Vec<CookieItem>,
+    #[serde(default)]
     pub last_login: Option<String>,
+    /// 退出登录 (会话已失效) 的时间
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+    /// TV扫码登录获得的app端令牌
+    #[serde(default)]
+    pub app_token: Option<AppToken>,
+    /// 网页端的bili_ticket，其Cookie形式同时保存在 `cookies` 中
+    #[serde(default)]
+    pub bili_ticket: Option<BiliTicket>,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            user_id: None,
-            username: None,
-            refresh_token: None,
-            cookies: None,
-            last_login: None,
+impl Profile {
+    /// 是否已退出登录
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// 标记为已退出登录，并清除已失效的Cookies和refresh_token
+    pub fn revoke(&mut self) {
+        self.cookies.clear();
+        self.refresh_token = None;
+        self.app_token = None;
+        self.revoked_at = Some(chrono::Local::now().to_string());
+    }
+
+    /// 获取可用的Cookies，账号已退出登录时返回错误
+    pub fn active_cookies(&self) -> Result<&[CookieItem]> {
+        if self.is_revoked() {
+            return Err(BiliError::ProfileError(format!(
+                "账号已退出登录，请重新登录: {}", self.display_name()
+            )).into());
+        }
+        Ok(&self.cookies)
+    }
+
+    /// 用于展示的名称: 别名 (若有) 或UID
+    pub fn display_name(&self) -> String {
+        match &self.alias {
+            Some(alias) => format!("{} ({})", alias, self.uid),
+            None => self.uid.to_string(),
+        }
+    }
+}
+
+/// 应用配置: 以UID为键的多账号存储
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    /// 未指定 `--profile` 时使用的账号
+    #[serde(default)]
+    pub default_profile: Option<u64>,
+    #[serde(default)]
+    pub profiles: BTreeMap<u64, Profile>,
+}
+
+/// 旧版单账号配置，读取时自动迁移
+#[derive(Debug, Deserialize)]
+struct LegacyConfig {
+    user_id: Option<u64>,
+    username: Option<String>,
+    refresh_token: Option<String>,
+    cookies: Option<Vec<CookieItem>>,
+    last_login: Option<String>,
+}
+
+impl From<LegacyConfig> for Config {
+    fn from(legacy: LegacyConfig) -> Self {
+        let mut config = Config::default();
+        if let Some(uid) = legacy.user_id {
+            let mut cookies = legacy.cookies.unwrap_or_default();
+            mark_legacy_placeholders(&mut cookies, legacy.refresh_token.as_deref());
+            config.profiles.insert(uid, Profile {
+                uid,
+                username: legacy.username.unwrap_or_default(),
+                alias: None,
+                refresh_token: legacy.refresh_token,
+                cookies,
+                last_login: legacy.last_login,
+                revoked_at: None,
+                app_token: None,
+                bili_ticket: None,
+            });
+            config.default_profile = Some(uid);
+        }
+        config
+    }
+}
+
+/// 最早的版本在缺少Cookie时会直接编造值且不加标记，迁移时按其规则识别出来并标记为合成值
+fn mark_legacy_placeholders(cookies: &mut [CookieItem], refresh_token: Option<&str>) {
+    let sessdata = cookies.iter()
+        .find(|c| c.name == "SESSDATA")
+        .map(|c| c.value.clone())
+        .unwrap_or_default();
+    for cookie in cookies.iter_mut() {
+        let fabricated = match cookie.name.as_str() {
+            // 用refresh_token冒充SESSDATA
+            "SESSDATA" => refresh_token == Some(cookie.value.as_str()),
+            "DedeUserID__ckMd5" => cookie.value.starts_with("placeholder_md5_"),
+            // `sid_` 加时间戳
+            "sid" => cookie.value.strip_prefix("sid_")
+                .is_some_and(|ts| !ts.is_empty() && ts.bytes().all(|b| b.is_ascii_digit())),
+            // 取SESSDATA的前32个字符
+            "bili_jct" => sessdata.len() >= 32 && sessdata.get(..32) == Some(cookie.value.as_str()),
+            _ => false,
+        };
+        if fabricated {
+            cookie.source = CookieSource::Synthesized;
         }
     }
 }
 
+impl Config {
+    /// 按UID或别名查找账号；未指定时使用默认账号，若只有一个账号则直接使用它
+    pub fn resolve_profile(&self, selector: Option<&str>) -> Result<&Profile> {
+        let uid = self.resolve_uid(selector)?;
+        Ok(&self.profiles[&uid])
+    }
+
+    /// 同 [`Config::resolve_profile`]，返回可变引用
+    pub fn resolve_profile_mut(&mut self, selector: Option<&str>) -> Result<&mut Profile> {
+        let uid = self.resolve_uid(selector)?;
+        Ok(self.profiles.get_mut(&uid).expect("UID已校验存在"))
+    }
+
+    fn resolve_uid(&self, selector: Option<&str>) -> Result<u64> {
+        match selector {
+            Some(selector) => {
+                let by_uid = selector.parse::<u64>().ok().filter(|uid| self.profiles.contains_key(uid));
+                let by_alias = || self.profiles.values()
+                    .find(|p| p.alias.as_deref() == Some(selector))
+                    .map(|p| p.uid);
+                by_uid.or_else(by_alias).ok_or_else(|| {
+                    BiliError::ProfileError(format!("找不到账号: {}", selector)).into()
+                })
+            }
+            None => {
+                if let Some(uid) = self.default_profile.filter(|uid| self.profiles.contains_key(uid)) {
+                    return Ok(uid);
+                }
+                match self.profiles.len() {
+                    0 => Err(BiliError::ProfileError("尚未保存任何账号，请先登录".to_string()).into()),
+                    1 => Ok(*self.profiles.keys().next().expect("只有一个账号")),
+                    _ => Err(BiliError::ProfileError(
+                        "保存了多个账号但未设置默认账号，请使用 --profile 指定".to_string()
+                    ).into()),
+                }
+            }
+        }
+    }
+
+    /// 检查别名的格式 (不能是纯数字)，返回已使用该别名的账号UID (若有)
+    ///
+    /// 可在登录前调用，以免扫码成功后才因别名无效而失败。
+    pub fn alias_owner(&self, alias: &str) -> Result<Option<u64>> {
+        if alias.parse::<u64>().is_ok() {
+            return Err(BiliError::ProfileError("别名不能是纯数字".to_string()).into());
+        }
+        Ok(self.profiles.values()
+            .find(|p| p.alias.as_deref() == Some(alias))
+            .map(|p| p.uid))
+    }
+
+    /// 设置账号别名，别名不能与其他账号重复，也不能是纯数字
+    pub fn rename_profile(&mut self, selector: &str, alias: &str) -> Result<()> {
+        let owner = self.alias_owner(alias)?;
+        let uid = self.resolve_uid(Some(selector))?;
+        if owner.is_some_and(|owner| owner != uid) {
+            return Err(BiliError::ProfileError(format!("别名已被其他账号使用: {}", alias)).into());
+        }
+        self.profiles.get_mut(&uid).expect("UID已校验存在").alias = Some(alias.to_string());
+        Ok(())
+    }
+
+    /// 删除账号，若删除的是默认账号则清除默认设置
+    pub fn remove_profile(&mut self, selector: &str) -> Result<Profile> {
+        let uid = self.resolve_uid(Some(selector))?;
+        if self.default_profile == Some(uid) {
+            self.default_profile = None;
+        }
+        Ok(self.profiles.remove(&uid).expect("UID已校验存在"))
+    }
+
+    /// 设置默认账号
+    pub fn set_default_profile(&mut self, selector: &str) -> Result<u64> {
+        let uid = self.resolve_uid(Some(selector))?;
+        self.default_profile = Some(uid);
+        Ok(uid)
+    }
+}
+
 /// 获取项目目录
-#[allow(dead_code)]
 pub fn get_project_dirs() -> Result<ProjectDirs> {
     ProjectDirs::from("rs", "bilicookies", "bilicookies-rs")
         .ok_or_else(|| BiliError::IoError(std::io::Error::new(
@@ -42,33 +271,39 @@ pub fn get_project_dirs() -> Result<ProjectDirs> {
 }
 
 /// 获取配置文件路径
-#[allow(dead_code)]
 pub fn get_config_path() -> Result<PathBuf> {
     let project_dirs = get_project_dirs()?;
     let config_dir = project_dirs.config_dir();
-    
+
     if !config_dir.exists() {
         fs::create_dir_all(config_dir)?;
     }
-    
+
     Ok(config_dir.join("config.json"))
 }
 
-/// 读取配置
-#[allow(dead_code)]
+/// 读取配置 (兼容旧版单账号配置)
 pub fn read_config() -> Result<Config> {
     let config_path = get_config_path()?;
-    
+
     if !config_path.exists() {
         return Ok(Config::default());
     }
-    
-    let config_str = fs::read_to_string(config_path)?;
-    Ok(serde_json::from_str(&config_str)?)
+
+    parse_config(&fs::read_to_string(config_path)?)
+}
+
+/// 解析配置文件内容，旧版单账号配置会迁移为多账号配置
+fn parse_config(config_str: &str) -> Result<Config> {
+    let value: serde_json::Value = serde_json::from_str(config_str)?;
+    if value.get("profiles").is_some() {
+        Ok(serde_json::from_value(value)?)
+    } else {
+        Ok(serde_json::from_value::<LegacyConfig>(value)?.into())
+    }
 }
 
 /// 保存配置
-#[allow(dead_code)]
 pub fn save_config(config: &Config) -> Result<()> {
     let config_path = get_config_path()?;
     let config_str = serde_json::to_string_pretty(config)?;
@@ -76,16 +311,193 @@ pub fn save_config(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// 按UID取得账号 (不存在则新建)，更新用户名和登录时间，并清除退出登录标记
+fn upsert_profile<'a>(config: &'a mut Config, user_id: u64, username: &str) -> &'a mut Profile {
+    if config.default_profile.is_none() {
+        config.default_profile = Some(user_id);
+    }
+
+    let profile = config.profiles.entry(user_id).or_insert_with(|| Profile {
+        uid: user_id,
+        ..Profile::default()
+    });
+    if !username.is_empty() {
+        profile.username = username.to_string();
+    }
+    profile.last_login = Some(chrono::Local::now().to_string());
+    profile.revoked_at = None;
+    profile
+}
+
 /// 保存cookies到配置
-#[allow(dead_code)]
+///
+/// 以UID为键新增或更新账号 (保留已有别名)；若尚无默认账号，则将其设为默认。
 pub fn save_cookies(cookies: &[CookieItem], user_id: u64, username: &str, refresh_token: &str) -> Result<()> {
     let mut config = read_config()?;
-    
-    config.user_id = Some(user_id);
-    config.username = Some(username.to_string());
-    config.refresh_token = Some(refresh_token.to_string());
-    config.cookies = Some(cookies.to_vec());
-    config.last_login = Some(chrono::Local::now().to_string());
-    
+
+    let profile = upsert_profile(&mut config, user_id, username);
+    profile.refresh_token = Some(refresh_token.to_string());
+    profile.cookies = cookies.to_vec();
+
+    save_config(&config)
+}
+
+/// 保存从外部文件导入的Cookies
+///
+/// 默认替换账号原有的Cookies：导入的Cookies属于新的会话，原有的refresh_token随之作废，替换为 `refresh_token` (若提供)。
+/// `merge` 为 `true` 时则将导入的Cookies合并到原有Cookies中 (同名的以导入的为准)，未提供 `refresh_token` 时保留原有的。
+pub fn save_imported_cookies(
+    cookies: &[CookieItem],
+    user_id: u64,
+    username: &str,
+    refresh_token: Option<&str>,
+    merge: bool,
+) -> Result<()> {
+    let mut config = read_config()?;
+
+    let profile = upsert_profile(&mut config, user_id, username);
+    if merge {
+        merge_cookies(&mut profile.cookies, cookies.to_vec());
+        if let Some(refresh_token) = refresh_token {
+            profile.refresh_token = Some(refresh_token.to_string());
+        }
+    } else {
+        profile.refresh_token = refresh_token.map(str::to_string);
+        profile.cookies = cookies.to_vec();
+    }
+
+    save_config(&config)
+}
+
+/// 保存TV扫码登录的结果: app端令牌及网页端Cookies
+///
+/// 网页端Cookies被替换为新会话时，原有的refresh_token属于旧会话，无法再用于刷新，因此一并清除。
+pub fn save_tv_login(result: &TvLoginResult) -> Result<()> {
+    let mut config = read_config()?;
+
+    let profile = upsert_profile(&mut config, result.uid, &result.username);
+    profile.app_token = Some(AppToken::from_tv_login(result));
+    if !result.cookies.is_empty() {
+        profile.cookies = result.cookies.clone();
+        profile.refresh_token = None;
+    }
+
+    save_config(&config)
+}
+
+/// 保存某个账号的bili_ticket
+pub fn save_ticket(user_id: u64, ticket: &BiliTicket) -> Result<()> {
+    let mut config = read_config()?;
+    let profile = config.resolve_profile_mut(Some(&user_id.to_string()))?;
+    profile.bili_ticket = Some(ticket.clone());
     save_config(&config)
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(uid: u64, alias: Option<&str>) -> Profile {
+        Profile {
+            uid,
+            username: format!("user{}", uid),
+            alias: alias.map(str::to_string),
+            ..Profile::default()
+        }
+    }
+
+    fn config(profiles: Vec<Profile>, default_profile: Option<u64>) -> Config {
+        Config {
+            default_profile,
+            profiles: profiles.into_iter().map(|p| (p.uid, p)).collect(),
+        }
+    }
+
+    #[test]
+    fn resolves_profiles() {
+        let stored = config(vec![profile(1, Some("work")), profile(2, None)], Some(2));
+        let uid = |selector| stored.resolve_profile(selector).map(|p| p.uid).ok();
+        assert_eq!(uid(Some("1")), Some(1));
+        assert_eq!(uid(Some("work")), Some(1));
+        assert_eq!(uid(None), Some(2));
+        assert_eq!(uid(Some("3")), None);
+        assert_eq!(uid(Some("home")), None);
+
+        // 只有一个账号时无需默认账号；多个账号且没有默认账号时必须指定
+        assert_eq!(config(vec![profile(1, None)], None).resolve_profile(None).unwrap().uid, 1);
+        assert!(config(vec![profile(1, None), profile(2, None)], None).resolve_profile(None).is_err());
+        assert!(config(vec![profile(1, None), profile(2, None)], Some(3)).resolve_profile(None).is_err());
+        assert!(Config::default().resolve_profile(None).is_err());
+    }
+
+    #[test]
+    fn renames_and_removes_profiles() {
+        let mut stored = config(vec![profile(1, Some("work")), profile(2, None)], Some(1));
+        assert!(stored.rename_profile("2", "work").is_err());
+        assert!(stored.rename_profile("2", "12345").is_err());
+        assert_eq!(stored.alias_owner("work").unwrap(), Some(1));
+        assert!(stored.alias_owner("42").is_err());
+
+        stored.rename_profile("work", "work").unwrap();
+        stored.rename_profile("2", "home").unwrap();
+        assert_eq!(stored.resolve_profile(Some("home")).unwrap().uid, 2);
+
+        let removed = stored.remove_profile("work").unwrap();
+        assert_eq!(removed.uid, 1);
+        assert_eq!(stored.default_profile, None);
+        assert_eq!(stored.resolve_profile(None).unwrap().uid, 2);
+        stored.remove_profile("home").unwrap();
+        assert!(stored.profiles.is_empty());
+    }
+
+    #[test]
+    fn migrates_legacy_config() {
+        let legacy = r#"{"user_id": 12345, "username": "bili", "refresh_token": "token",
+            "cookies": [{"name": "SESSDATA", "value": "abc", "domain": ".bilibili.com", "path": "/",
+                "expires": null, "http_only": true, "secure": true}],
+            "last_login": "2024-01-01"}"#;
+        let stored = parse_config(legacy).unwrap();
+        assert_eq!(stored.default_profile, Some(12345));
+        let profile = stored.resolve_profile(None).unwrap();
+        assert_eq!((profile.username.as_str(), profile.refresh_token.as_deref()), ("bili", Some("token")));
+        assert_eq!(profile.cookies[0].name, "SESSDATA");
+        assert!(!profile.is_revoked());
+
+        assert!(parse_config("{}").unwrap().profiles.is_empty());
+
+        let fabricated = r#"{"user_id": 12345, "refresh_token": "0123456789abcdef0123456789abcdef_token",
+            "cookies": [
+                {"name": "SESSDATA", "value": "0123456789abcdef0123456789abcdef_token", "domain": ".bilibili.com", "path": "/", "expires": null, "http_only": true, "secure": true},
+                {"name": "bili_jct", "value": "0123456789abcdef0123456789abcdef", "domain": ".bilibili.com", "path": "/", "expires": null, "http_only": false, "secure": false},
+                {"name": "DedeUserID", "value": "12345", "domain": ".bilibili.com", "path": "/", "expires": null, "http_only": false, "secure": false},
+                {"name": "DedeUserID__ckMd5", "value": "placeholder_md5_12345", "domain": ".bilibili.com", "path": "/", "expires": null, "http_only": false, "secure": false},
+                {"name": "sid", "value": "sid_1700000000", "domain": ".bilibili.com", "path": "/", "expires": null, "http_only": false, "secure": false},
+                {"name": "buvid3", "value": "b", "domain": ".bilibili.com", "path": "/", "expires": null, "http_only": false, "secure": false, "synthetic": true}
+            ]}"#;
+        let migrated = parse_config(fabricated).unwrap();
+        let synthetic: Vec<(&str, bool)> = migrated.profiles[&12345].cookies.iter()
+            .map(|c| (c.name.as_str(), c.is_synthetic()))
+            .collect();
+        assert_eq!(synthetic, [
+            ("SESSDATA", true), ("bili_jct", true), ("DedeUserID", false),
+            ("DedeUserID__ckMd5", true), ("sid", true), ("buvid3", true),
+        ]);
+        let current = serde_json::to_string(&stored).unwrap();
+        assert_eq!(parse_config(&current).unwrap().profiles[&12345].username, "bili");
+    }
+
+    #[test]
+    fn exports_app_token_separately() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let token = AppToken {
+            access_token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            expires_at: now + chrono::Duration::seconds(90),
+        };
+        let exported = serde_json::to_value(token.to_export(12345, now)).unwrap();
+        assert_eq!(exported["mid"], 12345);
+        assert_eq!(exported["access_token"], "access");
+        assert_eq!(exported["expires_in"], 90);
+        assert_eq!(token.to_export(12345, now + chrono::Duration::days(1)).expires_in, 0);
+    }
+}
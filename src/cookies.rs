@@ -1,13 +1,14 @@
 use anyhow::Result;
-use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
-use reqwest::header::{HeaderMap, SET_COOKIE};
+use chrono::{DateTime, Utc};
+use reqwest::{header::{HeaderMap, SET_COOKIE}, Url};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
-use crate::{auth::LoginResult, error::BiliError};
+use crate::{api::TvCookieInfo, auth::LoginResult, error::{BiliError, MissingCookie}, fingerprint::fetch_fingerprint_cookies};
+use crate::{jar::is_bilibili_domain, set_cookie::parse_set_cookie};
 
 /// 存储Cookie信息的结构体
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "StoredCookieItem")]
 pub struct CookieItem {
     pub name: String,
     pub value: String,
@@ -16,412 +17,283 @@ pub struct CookieItem {
     pub expires: Option<DateTime<Utc>>,
     pub http_only: bool,
     pub secure: bool,
+    pub same_site: Option<SameSite>,
+    /// CHIPS分区Cookie
+    pub partitioned: bool,
+    /// Chromium的Cookie优先级
+    pub priority: Option<Priority>,
+    /// 仅发送给 `domain` 本身而不包括其子域名 (此时 `domain` 不以 `.` 开头)
+    pub host_only: bool,
+    pub creation_time: Option<DateTime<Utc>>,
+    /// 最近一次随请求发送的时间
+    pub last_access: Option<DateTime<Utc>>,
+    pub source: CookieSource,
 }
 
-/// 从登录结果中提取cookies
-pub async fn extract_cookies(login_result: &LoginResult) -> Result<Vec<CookieItem>> {
-    let mut cookies = Vec::new();
-    
-    // 使用多种方法提取cookies，确保尽可能全面获取
-    
-    // 1. 尝试从passport登录信息接口获取完整的cookie信息
-    if let Ok(passport_response) = login_result.client.get("https://passport.bilibili.com/x/passport-login/web/cookie/info")
-        .send()
-        .await {
-        
-        // 尝试从响应头中提取cookies
-        if let Ok(header_cookies) = parse_cookies(passport_response.headers()) {
-            for cookie in header_cookies {
-                if !cookies.iter().any(|c: &CookieItem| c.name == cookie.name) {
-                    cookies.push(cookie);
-                }
-            }
-        }
-        
-        // 尝试从响应体中获取Cookie信息
-        if let Ok(passport_body) = passport_response.text().await {
-            if let Ok(json) = serde_json::from_str::<Value>(&passport_body) {
-                if let Some(data) = json.get("data") {
-                    // 从cookie_info中提取
-                    if let Some(cookie_info) = data.get("cookie_info") {
-                        if let Some(cookie_array) = cookie_info.get("cookies").and_then(|c| c.as_array()) {
-                            // 获取域名信息
-                            let domain = cookie_info.get("domains")
-                                .and_then(|d| d.as_array())
-                                .and_then(|d| d.first())
-                                .and_then(|d| d.as_str())
-                                .unwrap_or(".bilibili.com");
-                            
-                            // 处理每个cookie
-                            for cookie in cookie_array {
-                                let name = cookie.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string();
-                                let value = cookie.get("value").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                let expires = cookie.get("expires").and_then(|e| e.as_i64());
-                                let http_only = cookie.get("http_only").and_then(|h| h.as_i64()).unwrap_or(0) == 1;
-                                let secure = cookie.get("secure").and_then(|s| s.as_i64()).unwrap_or(0) == 1;
-                                
-                                if !name.is_empty() && !value.is_empty() && !cookies.iter().any(|c: &CookieItem| c.name == name) {
-                                    cookies.push(CookieItem {
-                                        name,
-                                        value,
-                                        domain: domain.to_string(),
-                                        path: "/".to_string(),
-                                        expires: expires.map(|e| {
-                                            // 正确处理时间戳转换
-                                            let dt = chrono::DateTime::from_timestamp(e, 0);
-                                            match dt {
-                                                Some(dt) => dt,
-                                                None => Utc::now(),
-                                            }
-                                        }),
-                                        http_only,
-                                        secure,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                    
-                    // 从token_info中提取信息
-                    if let Some(token_info_val) = data.get("token_info") {
-                        let mid = token_info_val.get("mid").and_then(|m| m.as_u64()).unwrap_or(0);
-                        if mid > 0 && !cookies.iter().any(|c: &CookieItem| c.name == "DedeUserID") {
-                            cookies.push(CookieItem {
-                                name: "DedeUserID".to_string(),
-                                value: mid.to_string(),
-                                domain: ".bilibili.com".to_string(),
-                                path: "/".to_string(),
-                                expires: None,
-                                http_only: false,
-                                secure: false,
-                            });
-                        }
-                        
-                        // refresh_token 处理
-                        if let Some(refresh_token_json_val) = token_info_val.get("refresh_token") {
-                            let refresh_token_str = refresh_token_json_val.as_str().unwrap_or("").to_string();
-                            // 确保SESSDATA (如果不存在且refresh_token非空才用其填充)
-                            if !refresh_token_str.is_empty() && !cookies.iter().any(|c: &CookieItem| c.name == "SESSDATA") {
-                                cookies.push(CookieItem {
-                                    name: "SESSDATA".to_string(), 
-                                    value: refresh_token_str.clone(),
-                                    domain: ".bilibili.com".to_string(),
-                                    path: "/".to_string(),
-                                    expires: None,
-                                    http_only: true,
-                                    secure: true,
-                                });
-                            }
-                            // 始终尝试添加 refresh_token CookieItem, 即使其值为空
-                            if !cookies.iter().any(|c: &CookieItem| c.name == "refresh_token") {
-                                cookies.push(CookieItem {
-                                    name: "refresh_token".to_string(),
-                                    value: refresh_token_str, // value 可能为空
-                                    domain: ".bilibili.com".to_string(), 
-                                    path: "/".to_string(),
-                                    expires: None, 
-                                    http_only: false, 
-                                    secure: false,  
-                                });
-                            }
-                        }
-
-                        // access_token 处理
-                        if let Some(access_token_json_val) = token_info_val.get("access_token") {
-                            let access_token_str = access_token_json_val.as_str().unwrap_or("").to_string();
-                            // 始终尝试添加 access_token CookieItem, 即使其值为空
-                            if !cookies.iter().any(|c: &CookieItem| c.name == "access_token") {
-                                cookies.push(CookieItem {
-                                    name: "access_token".to_string(),
-                                    value: access_token_str, // value 可能为空
-                                    domain: ".bilibili.com".to_string(), 
-                                    path: "/".to_string(),
-                                    expires: None, 
-                                    http_only: false, 
-                                    secure: false,  
-                                });
-                            }
-                        }
-
-                        // Extract expires_in for access_token
-                        if let Some(expires_in_num) = token_info_val.get("expires_in").and_then(|e| e.as_i64()) {
-                             if !cookies.iter().any(|c: &CookieItem| c.name == "access_token_expires_in") {
-                                // Calculate expiry date for access_token
-                                let now = Utc::now();
-                                let expires_dt = now + chrono::Duration::seconds(expires_in_num);
-
-                                cookies.push(CookieItem {
-                                    name: "access_token_expires_in".to_string(),
-                                    value: expires_in_num.to_string(), // Store original seconds
-                                    domain: ".bilibili.com".to_string(),
-                                    path: "/".to_string(),
-                                    expires: Some(expires_dt), // Store calculated expiry DateTime
-                                    http_only: false,
-                                    secure: false,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
+/// 读取时的CookieItem，缺少的字段取默认值，以兼容旧版本保存或导出的Cookies
+#[derive(Deserialize)]
+struct StoredCookieItem {
+    name: String,
+    value: String,
+    domain: String,
+    path: String,
+    expires: Option<DateTime<Utc>>,
+    http_only: bool,
+    secure: bool,
+    #[serde(default)]
+    same_site: Option<SameSite>,
+    #[serde(default)]
+    partitioned: bool,
+    #[serde(default)]
+    priority: Option<Priority>,
+    #[serde(default)]
+    host_only: bool,
+    #[serde(default)]
+    creation_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    last_access: Option<DateTime<Utc>>,
+    #[serde(default)]
+    source: Option<CookieSource>,
+    /// 旧版本以此标记合成值，后被 `source` 取代
+    #[serde(default)]
+    synthetic: bool,
+}
+
+impl From<StoredCookieItem> for CookieItem {
+    fn from(stored: StoredCookieItem) -> Self {
+        Self {
+            name: stored.name,
+            value: stored.value,
+            domain: stored.domain,
+            path: stored.path,
+            expires: stored.expires,
+            http_only: stored.http_only,
+            secure: stored.secure,
+            same_site: stored.same_site,
+            partitioned: stored.partitioned,
+            priority: stored.priority,
+            host_only: stored.host_only,
+            creation_time: stored.creation_time,
+            last_access: stored.last_access,
+            source: CookieSource::from_legacy(stored.source, stored.synthetic),
         }
     }
-    
-    // 2. 尝试从用户信息接口获取cookies
-    if let Ok(nav_response) = login_result.client.get("https://api.bilibili.com/x/web-interface/nav")
-        .send()
-        .await {
-        
-        // 先保存响应头以供后续使用
-        let nav_headers = nav_response.headers().clone();
-        
-        // 尝试从响应头中提取cookies
-        if let Ok(header_cookies) = parse_cookies(&nav_headers) {
-            for cookie in header_cookies {
-                if !cookies.iter().any(|c: &CookieItem| c.name == cookie.name) {
-                    cookies.push(cookie);
-                }
-            }
-        }
-        
-        if let Ok(body_text) = nav_response.text().await {
-            if let Ok(json) = serde_json::from_str::<Value>(&body_text) {
-                if let Some(data) = json.get("data") {
-                    // 提取用户ID
-                    if let Some(mid) = data.get("mid").and_then(|v| v.as_u64()) {
-                        if mid > 0 && !cookies.iter().any(|c: &CookieItem| c.name == "DedeUserID") {
-                            cookies.push(CookieItem {
-                                name: "DedeUserID".to_string(),
-                                value: mid.to_string(),
-                                domain: ".bilibili.com".to_string(),
-                                path: "/".to_string(),
-                                expires: None,
-                                http_only: false,
-                                secure: false,
-                            });
-                        }
-                    }
-                    
-                    // 提取CSRF令牌
-                    if let Some(csrf) = data.get("csrf").and_then(|v| v.as_str()) {
-                        if !csrf.is_empty() && !cookies.iter().any(|c: &CookieItem| c.name == "bili_jct") {
-                            cookies.push(CookieItem {
-                                name: "bili_jct".to_string(),
-                                value: csrf.to_string(),
-                                domain: ".bilibili.com".to_string(),
-                                path: "/".to_string(),
-                                expires: None,
-                                http_only: false,
-                                secure: false,
-                            });
-                        }
-                    }
-                    
-                    // 提取是否登录状态和SESSDATA
-                    if data.get("isLogin").and_then(|v| v.as_bool()).unwrap_or(false) {
-                        if !cookies.iter().any(|c: &CookieItem| c.name == "SESSDATA") && !login_result.refresh_token.is_empty() {
-                            cookies.push(CookieItem {
-                                name: "SESSDATA".to_string(),
-                                value: login_result.refresh_token.clone(),
-                                domain: ".bilibili.com".to_string(),
-                                path: "/".to_string(),
-                                expires: None,
-                                http_only: true,
-                                secure: true,
-                            });
-                        }
-                    }
-                }
-            }
-        }
+}
+
+impl CookieItem {
+    /// 是否为本工具合成的值 (非服务器下发)，仅在宽松模式下出现
+    pub fn is_synthetic(&self) -> bool {
+        self.source == CookieSource::Synthesized
     }
-    
-    // 3. 尝试从个人空间页面获取cookies
-    if let Ok(space_response) = login_result.client.get("https://space.bilibili.com")
-        .send()
-        .await {
-        
-        // 先保存响应头以供后续使用
-        let space_headers = space_response.headers().clone();
-        
-        if let Ok(space_cookies) = parse_cookies(&space_headers) {
-            for cookie in space_cookies {
-                if !cookies.iter().any(|c: &CookieItem| c.name == cookie.name) {
-                    cookies.push(cookie);
-                }
-            }
-        }
-        
-        // 尝试从页面内容提取bili_jct
-        if !cookies.iter().any(|c: &CookieItem| c.name == "bili_jct") {
-            if let Ok(html) = space_response.text().await {
-                // 尝试寻找CSRF相关的JavaScript变量
-                if let Some(start_idx) = html.find("\"bili_jct\":") {
-                    let substr = &html[start_idx + 11..];
-                    if let Some(end_idx) = substr.find("\"") {
-                        let start_value_idx = substr.find("\"").map(|i| i + 1).unwrap_or(0);
-                        if start_value_idx > 0 && end_idx > start_value_idx {
-                            let value = &substr[start_value_idx..end_idx];
-                            if !value.is_empty() {
-                                cookies.push(CookieItem {
-                                    name: "bili_jct".to_string(),
-                                    value: value.to_string(),
-                                    domain: ".bilibili.com".to_string(),
-                                    path: "/".to_string(),
-                                    expires: None,
-                                    http_only: false,
-                                    secure: false,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
+
+    /// 是否为以Cookie形式出现的app端令牌条目 (旧版本曾这样导出)，它们并不是浏览器Cookie
+    pub fn is_app_token_entry(&self) -> bool {
+        APP_TOKEN_ENTRY_NAMES.contains(&self.name.as_str())
     }
-    
-    // 4. 尝试从主页提取bili_jct
-    if !cookies.iter().any(|c: &CookieItem| c.name == "bili_jct") {
-        if let Ok(main_response) = login_result.client.get("https://www.bilibili.com")
-            .send()
-            .await {
-            
-            if let Ok(main_cookies) = parse_cookies(main_response.headers()) {
-                for cookie in main_cookies {
-                    if !cookies.iter().any(|c: &CookieItem| c.name == cookie.name) {
-                        cookies.push(cookie);
-                    }
-                }
-            }
-            
-            // 从页面内容提取bili_jct
-            if let Ok(html) = main_response.text().await {
-                if let Some(start_idx) = html.find("\"bili_jct\":") {
-                    let substr = &html[start_idx + 11..];
-                    if let Some(end_idx) = substr.find(",") {
-                        let value = substr[..end_idx].trim_matches('"');
-                        if !value.is_empty() {
-                            cookies.push(CookieItem {
-                                name: "bili_jct".to_string(),
-                                value: value.to_string(),
-                                domain: ".bilibili.com".to_string(),
-                                path: "/".to_string(),
-                                expires: None,
-                                http_only: false,
-                                secure: false,
-                            });
-                        }
-                    }
-                }
-            }
+}
+
+/// app端令牌的字段名，见 [`crate::config::AppTokenExport`]
+pub const APP_TOKEN_ENTRY_NAMES: [&str; 4] = ["access_token", "refresh_token", "expires_in", "mid"];
+
+/// Cookie的来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CookieSource {
+    /// 扫码登录轮询的结果 (网页端的跨域登录URL或TV端的cookie_info)
+    PollUrl,
+    /// 响应头中的 `Set-Cookie`
+    SetCookie,
+    /// nav接口响应体中的用户信息
+    NavBody,
+    /// 其他接口的响应体 (如finger/spi、GenWebTicket、TV端登录令牌)
+    ApiBody,
+    /// 按网页端脚本的规则在本地生成 (如 `_uuid`、`b_lsid`)
+    Generated,
+    /// 宽松模式下合成的替代值，并非服务器下发
+    Synthesized,
+    /// 从外部文件导入，来源未知
+    #[default]
+    Imported,
+}
+
+impl CookieSource {
+    /// 没有 `source` 时按旧版本的 `synthetic` 标记推断：合成值为 `Synthesized`，否则来源未知
+    pub(crate) fn from_legacy(source: Option<Self>, synthetic: bool) -> Self {
+        match source {
+            Some(source) => source,
+            None if synthetic => Self::Synthesized,
+            None => Self::Imported,
         }
     }
+}
+
+/// Cookie的SameSite属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+/// Cookie的Priority属性 (Chromium扩展)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+/// 将TV端登录返回的cookie_info转换为CookieItem
+pub fn from_tv_cookie_info(cookie_info: &TvCookieInfo) -> Vec<CookieItem> {
+    let domain = cookie_info.domains.first()
+        .cloned()
+        .unwrap_or_else(|| ".bilibili.com".to_string());
     
-    // 5. 从账户页面获取bili_jct
-    if !cookies.iter().any(|c: &CookieItem| c.name == "bili_jct") {
-        if let Ok(account_response) = login_result.client.get("https://account.bilibili.com/account/home")
-            .send()
-            .await {
-            
-            if let Ok(account_cookies) = parse_cookies(account_response.headers()) {
-                for cookie in account_cookies {
-                    if !cookies.iter().any(|c: &CookieItem| c.name == cookie.name) {
-                        cookies.push(cookie);
-                    }
-                }
-            }
-            
-            // 从页面内容提取bili_jct
-            if let Ok(html) = account_response.text().await {
-                // 方法1: 寻找变量定义
-                if let Some(start_idx) = html.find("\"bili_jct\":") {
-                    let substr = &html[start_idx + 11..];
-                    if let Some(end_idx) = substr.find(",") {
-                        let value = substr[..end_idx].trim_matches('"');
-                        if !value.is_empty() {
-                            cookies.push(CookieItem {
-                                name: "bili_jct".to_string(),
-                                value: value.to_string(),
-                                domain: ".bilibili.com".to_string(),
-                                path: "/".to_string(),
-                                expires: None,
-                                http_only: false,
-                                secure: false,
-                            });
-                        }
-                    }
-                } 
-                // 方法2: 寻找隐藏表单字段
-                else if let Some(start_idx) = html.find("name=\"csrf\"") {
-                    let value_start = html[start_idx..].find("value=\"").map(|i| start_idx + i + 7).unwrap_or(0);
-                    if value_start > 0 {
-                        let substr = &html[value_start..];
-                        if let Some(end_idx) = substr.find("\"") {
-                            let value = &substr[..end_idx];
-                            if !value.is_empty() {
-                                cookies.push(CookieItem {
-                                    name: "bili_jct".to_string(),
-                                    value: value.to_string(),
-                                    domain: ".bilibili.com".to_string(),
-                                    path: "/".to_string(),
-                                    expires: None,
-                                    http_only: false,
-                                    secure: false,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let now = Utc::now();
+    cookie_info.cookies.iter()
+        .filter(|c| !c.name.is_empty() && !c.value.is_empty())
+        .map(|c| CookieItem {
+            name: c.name.clone(),
+            value: c.value.clone(),
+            domain: domain.clone(),
+            path: "/".to_string(),
+            expires: (c.expires > 0).then(|| DateTime::from_timestamp(c.expires, 0)).flatten(),
+            http_only: c.http_only == 1,
+            secure: c.secure == 1,
+            same_site: None,
+            partitioned: false,
+            priority: None,
+            host_only: false,
+            creation_time: Some(now),
+            last_access: None,
+            source: CookieSource::PollUrl,
+        })
+        .collect()
+}
+
+/// 登录URL中携带的Cookie名称
+const LOGIN_URL_COOKIE_NAMES: [&str; 4] = ["DedeUserID", "DedeUserID__ckMd5", "SESSDATA", "bili_jct"];
+
+/// 从扫码成功返回的crossDomain URL中解析登录Cookies
+///
+/// URL的查询参数包含 `DedeUserID`、`DedeUserID__ckMd5`、`SESSDATA`、`bili_jct`、
+/// `Expires` (过期时间戳，秒) 和 `gourl`。Cookie值保持URL中的编码形式，与浏览器中存储的一致。
+pub fn parse_login_url(login_url: &str) -> Result<Vec<CookieItem>> {
+    let url = Url::parse(login_url)
+        .map_err(|e| BiliError::CookieError(format!("无法解析登录URL: {}", e)))?;
+    let query = url.query().unwrap_or_default();
     
-    // 6. 从cookie_store或请求头中提取cookie
-    let cookie_domains = vec![
-        ".bilibili.com", 
-        "bilibili.com", 
-        "www.bilibili.com", 
-        "account.bilibili.com",
-        "api.bilibili.com"
-    ];
+    let params: Vec<(&str, &str)> = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
     
-    for domain in cookie_domains {
-        if let Ok(res) = login_result.client.get(&format!("https://{}/favicon.ico", domain))
-            .send()
-            .await {
-            
-            if let Ok(more_cookies) = parse_cookies(res.headers()) {
-                for cookie in more_cookies {
-                    if !cookies.iter().any(|c: &CookieItem| c.name == cookie.name) {
-                        cookies.push(cookie);
-                    }
-                }
-            }
+    let expires = params.iter()
+        .find(|(key, _)| *key == "Expires")
+        .and_then(|(_, value)| value.parse::<i64>().ok())
+        .and_then(|ts| DateTime::from_timestamp(ts, 0));
+    
+    let now = Utc::now();
+    let cookies = params.iter()
+        .filter(|(key, value)| LOGIN_URL_COOKIE_NAMES.contains(key) && !value.is_empty())
+        .map(|(name, value)| CookieItem {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: ".bilibili.com".to_string(),
+            path: "/".to_string(),
+            expires,
+            http_only: *name == "SESSDATA",
+            secure: *name == "SESSDATA",
+            same_site: None,
+            partitioned: false,
+            priority: None,
+            host_only: false,
+            creation_time: Some(now),
+            last_access: None,
+            source: CookieSource::PollUrl,
+        })
+        .collect();
+    
+    Ok(cookies)
+}
+
+/// 必须存在的登录Cookie
+pub const REQUIRED_COOKIE_NAMES: [&str; 4] = ["SESSDATA", "bili_jct", "DedeUserID", "DedeUserID__ckMd5"];
+
+/// Cookie提取模式
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ExtractMode {
+    /// 严格模式：缺少的Cookie直接报错，绝不合成Cookie值
+    #[default]
+    Strict,
+    /// 宽松模式：为缺少的Cookie合成替代值，其来源标记为 [`CookieSource::Synthesized`]
+    Lenient,
+}
+
+/// 从登录结果中提取cookies (严格模式)
+pub async fn extract_cookies(login_result: &LoginResult) -> Result<Vec<CookieItem>> {
+    extract_cookies_with_mode(login_result, ExtractMode::Strict).await
+}
+
+/// 仍缺少这些Cookie时才访问一次主页，让服务器补发
+const HOMEPAGE_FALLBACK_NAMES: [&str; 2] = ["bili_jct", "sid"];
+
+/// 按指定模式从登录结果中提取cookies
+///
+/// 登录过程中 (轮询、nav接口) 服务器下发的Cookie都已记录在 [`LoginResult::cookie_store`] 中，
+/// 直接从中枚举即可取得完整的域名、路径、过期时间等属性，无需再逐个请求各页面。
+pub async fn extract_cookies_with_mode(login_result: &LoginResult, mode: ExtractMode) -> Result<Vec<CookieItem>> {
+    // 0. 扫码成功URL中的Cookies最为权威，优先使用
+    let mut cookies = parse_login_url(&login_result.login_url).unwrap_or_default();
+    
+    // 1. 合并登录过程中Cookie存储记录下的Cookies
+    add_missing_cookies(&mut cookies, login_result.cookie_store.bilibili_cookies());
+    
+    // 2. 仍缺少重要Cookie时访问一次主页，再从Cookie存储中补充
+    let lacks_important = HOMEPAGE_FALLBACK_NAMES.iter()
+        .any(|name| !cookies.iter().any(|c| c.name == *name));
+    if lacks_important && login_result.client.get("https://www.bilibili.com").send().await.is_ok() {
+        add_missing_cookies(&mut cookies, login_result.cookie_store.bilibili_cookies());
+    }
+    
+    // 3. 获取buvid3/buvid4/b_nut/_uuid/b_lsid等指纹Cookie，缺少它们的请求容易触发风控 (-352)
+    if !cookies.iter().any(|c: &CookieItem| c.name == "buvid3") {
+        if let Ok(fingerprint_cookies) = fetch_fingerprint_cookies(&login_result.client).await {
+            add_missing_cookies(&mut cookies, fingerprint_cookies);
         }
     }
     
-    // 7. 确保所有重要的cookie都存在
-    ensure_important_cookies(&mut cookies, login_result);
+    // 4. 确保所有重要的cookie都存在
+    ensure_uid_cookie(&mut cookies, login_result);
+    if mode == ExtractMode::Lenient {
+        synthesize_missing_cookies(&mut cookies, login_result);
+    }
     
-    // 8. 恢复过滤掉值为空的Cookie的逻辑
+    // 5. 过滤掉值为空的Cookie
     cookies.retain(|c| !c.value.is_empty()); 
     
+    if mode == ExtractMode::Strict {
+        check_required_cookies(&cookies)?;
+    }
+    
     if cookies.is_empty() {
         return Err(BiliError::CookieError("未找到B站相关的Cookie".to_string()).into());
     }
     
-    // 9. 标准化cookie domain，确保所有cookie都有正确的domain
-    for cookie in &mut cookies {
-        if !cookie.domain.starts_with('.') && cookie.domain.contains("bilibili.com") {
-            cookie.domain = format!(".{}", cookie.domain);
+    Ok(cookies)
+}
+
+/// 加入尚不存在的同名cookie，已有的cookie保持不变
+fn add_missing_cookies(cookies: &mut Vec<CookieItem>, candidates: Vec<CookieItem>) {
+    for candidate in candidates {
+        if !cookies.iter().any(|c| c.name == candidate.name) {
+            cookies.push(candidate);
         }
     }
-    
-    Ok(cookies)
 }
 
-/// 确保所有重要的cookie都存在
-fn ensure_important_cookies(cookies: &mut Vec<CookieItem>, login_result: &LoginResult) {
+/// 确保有DedeUserID (其值即登录用户的UID，并非合成)
+fn ensure_uid_cookie(cookies: &mut Vec<CookieItem>, login_result: &LoginResult) {
     // 确保有DedeUserID
     if !cookies.iter().any(|c: &CookieItem| c.name == "DedeUserID") && login_result.uid != 0 {
         cookies.push(CookieItem {
@@ -432,8 +304,20 @@ fn ensure_important_cookies(cookies: &mut Vec<CookieItem>, login_result: &LoginR
             expires: None,
             http_only: false,
             secure: false,
+            same_site: None,
+            partitioned: false,
+            priority: None,
+            host_only: false,
+            creation_time: Some(Utc::now()),
+            last_access: None,
+            source: CookieSource::NavBody,
         });
     }
+}
+
+/// 为缺少的重要cookie合成替代值，所有合成的cookie的来源都标记为 [`CookieSource::Synthesized`]
+fn synthesize_missing_cookies(cookies: &mut Vec<CookieItem>, login_result: &LoginResult) {
+    let now = Utc::now();
     
     // 确保有SESSDATA
     if !cookies.iter().any(|c: &CookieItem| c.name == "SESSDATA") && !login_result.refresh_token.is_empty() {
@@ -445,6 +329,13 @@ fn ensure_important_cookies(cookies: &mut Vec<CookieItem>, login_result: &LoginR
             expires: None,
             http_only: true,
             secure: true,
+            same_site: None,
+            partitioned: false,
+            priority: None,
+            host_only: false,
+            creation_time: Some(now),
+            last_access: None,
+            source: CookieSource::Synthesized,
         });
     }
     
@@ -465,6 +356,13 @@ fn ensure_important_cookies(cookies: &mut Vec<CookieItem>, login_result: &LoginR
                 expires: None,
                 http_only: false,
                 secure: false,
+                same_site: None,
+                partitioned: false,
+                priority: None,
+                host_only: false,
+                creation_time: Some(now),
+                last_access: None,
+                source: CookieSource::Synthesized,
             });
         }
     }
@@ -484,6 +382,13 @@ fn ensure_important_cookies(cookies: &mut Vec<CookieItem>, login_result: &LoginR
             expires: None,
             http_only: false,
             secure: false,
+            same_site: None,
+            partitioned: false,
+            priority: None,
+            host_only: false,
+            creation_time: Some(now),
+            last_access: None,
+            source: CookieSource::Synthesized,
         });
     }
     
@@ -504,132 +409,62 @@ fn ensure_important_cookies(cookies: &mut Vec<CookieItem>, login_result: &LoginR
                     expires: None,
                     http_only: false,
                     secure: false,
+                    same_site: None,
+                    partitioned: false,
+                    priority: None,
+                    host_only: false,
+                    creation_time: Some(now),
+                    last_access: None,
+                    source: CookieSource::Synthesized,
                 });
             }
         }
     }
 }
 
-/// 从响应头中解析cookies
-fn parse_cookies(headers: &HeaderMap) -> Result<Vec<CookieItem>> {
-    let mut cookies = Vec::new();
+/// 检查必要的cookie是否齐全，缺少时返回列出缺失项及原因的错误
+pub fn check_required_cookies(cookies: &[CookieItem]) -> Result<()> {
+    let missing: Vec<MissingCookie> = REQUIRED_COOKIE_NAMES.iter()
+        .filter(|name| !cookies.iter().any(|c| c.name == **name && !c.value.is_empty()))
+        .map(|name| MissingCookie {
+            name: name.to_string(),
+            reason: missing_cookie_reason(name).to_string(),
+        })
+        .collect();
     
-    for cookie_header in headers.get_all(SET_COOKIE) {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookie_parts: Vec<&str> = cookie_str.split(';').collect();
-            if cookie_parts.is_empty() {
-                continue;
-            }
-            
-            let name_value: Vec<&str> = cookie_parts[0].splitn(2, '=').collect(); // splitn(2, '=') to handle values with '='
-            if name_value.len() < 2 {
-                continue;
-            }
-            
-            let name = name_value[0].trim().to_string();
-            let value = name_value[1].trim().to_string();
-            
-            let mut domain = ".bilibili.com".to_string(); // Default domain
-            let mut path = "/".to_string(); // Default path
-            let mut expires_dt: Option<DateTime<Utc>> = None;
-            let mut http_only = false;
-            let mut secure = false;
-            
-            for part in &cookie_parts[1..] {
-                let attr_parts: Vec<&str> = part.splitn(2, '=').collect();
-                if attr_parts.is_empty() {
-                    continue;
-                }
-                let attr_name = attr_parts[0].trim().to_lowercase();
-                
-                match attr_name.as_str() {
-                    "domain" => {
-                        if attr_parts.len() > 1 {
-                            domain = attr_parts[1].trim().to_string();
-                        }
-                    },
-                    "path" => {
-                        if attr_parts.len() > 1 {
-                            path = attr_parts[1].trim().to_string();
-                        }
-                    },
-                    "expires" => {
-                        if attr_parts.len() > 1 {
-                            let date_str = attr_parts[1].trim();
-                            // Try common cookie date formats
-                            if let Ok(dt) = DateTime::parse_from_rfc2822(date_str) {
-                                expires_dt = Some(dt.with_timezone(&Utc));
-                            } else if let Ok(dt) = NaiveDateTime::parse_from_str(date_str, "%a, %d-%b-%Y %H:%M:%S GMT") {
-                                expires_dt = Some(DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc));
-                            } else if let Ok(dt) = NaiveDateTime::parse_from_str(date_str, "%a, %d %b %Y %H:%M:%S GMT") {
-                                 expires_dt = Some(DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc));
-                            }
-                            // Add more formats if necessary, e.g., with different timezone abbreviations or no timezone
-                        }
-                    },
-                    "max-age" => {
-                        if attr_parts.len() > 1 {
-                            if let Ok(seconds) = attr_parts[1].trim().parse::<i64>() {
-                                if seconds <= 0 {
-                                    expires_dt = Some(Utc.timestamp_opt(0, 0).single().unwrap_or_else(Utc::now)); // Expire immediately
-                                } else {
-                                    expires_dt = Some(Utc::now() + chrono::Duration::seconds(seconds));
-                                }
-                            }
-                        }
-                    },
-                    "httponly" => http_only = true,
-                    "secure" => secure = true,
-                    _ => {}
-                }
-            }
-            
-            if domain.contains("bilibili") || domain.contains("bili") || 
-               name.eq_ignore_ascii_case("SESSDATA") || name.eq_ignore_ascii_case("bili_jct") || 
-               name.eq_ignore_ascii_case("DedeUserID") || name.eq_ignore_ascii_case("DedeUserID__ckMd5") ||
-               name.eq_ignore_ascii_case("sid") || name.contains("bili") {
-                cookies.push(CookieItem {
-                    name,
-                    value,
-                    domain,
-                    path,
-                    expires: expires_dt,
-                    http_only,
-                    secure,
-                });
-            }
-        }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(BiliError::MissingCookies(missing).into())
     }
-    
-    if cookies.is_empty() && headers.contains_key("Cookie") {
-        if let Some(cookie_header) = headers.get("Cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                for cookie_part in cookie_str.split(';') {
-                    let parts: Vec<&str> = cookie_part.splitn(2, '=').collect();
-                    if parts.len() >= 2 {
-                        let name = parts[0].trim().to_string();
-                        let value = parts[1].trim().to_string();
-                        
-                        cookies.push(CookieItem {
-                            name,
-                            value,
-                            domain: ".bilibili.com".to_string(),
-                            path: "/".to_string(),
-                            expires: None,
-                            http_only: false,
-                            secure: false,
-                        });
-                    }
-                }
-            }
-        }
+}
+
+/// 说明某个必要cookie缺失时已经尝试过的来源
+fn missing_cookie_reason(name: &str) -> &'static str {
+    match name {
+        "SESSDATA" => "扫码登录URL和登录过程中的Set-Cookie中均未出现 (不会再用refresh_token代替)",
+        "bili_jct" => "扫码登录URL、登录过程及主页的Set-Cookie中均未出现",
+        "DedeUserID" => "扫码登录URL中没有，nav接口也未返回用户ID",
+        "DedeUserID__ckMd5" => "扫码登录URL和登录过程中的Set-Cookie中均未出现",
+        _ => "服务器未下发该Cookie",
     }
-    
-    Ok(cookies)
+}
+
+/// 按RFC 6265从响应头中解析B站的cookies
+///
+/// `request_url` 为产生该响应的请求URL，用于确定host-only Cookie的域名及默认路径。
+pub fn parse_cookies(headers: &HeaderMap, request_url: &Url) -> Vec<CookieItem> {
+    let now = Utc::now();
+    headers.get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .filter_map(parse_set_cookie)
+        .filter_map(|cookie| cookie.into_cookie_item(request_url, now))
+        .filter(|cookie| is_bilibili_domain(&cookie.domain))
+        .collect()
 }
 
 /// 获取重要的Cookie
-#[allow(dead_code)]
 pub fn get_important_cookies(cookies: &[CookieItem]) -> Vec<CookieItem> {
     let important_names = ["SESSDATA", "bili_jct", "DedeUserID", "DedeUserID__ckMd5", "sid"];
     
@@ -640,13 +475,11 @@ pub fn get_important_cookies(cookies: &[CookieItem]) -> Vec<CookieItem> {
 }
 
 /// 将CookieItem转换为Cookie字符串
-#[allow(dead_code)]
 pub fn cookie_to_string(cookie: &CookieItem) -> String {
     format!("{}={}", cookie.name, cookie.value)
 }
 
 /// 将多个CookieItem合并为单个Cookie字符串
-#[allow(dead_code)]
 pub fn cookies_to_header_string(cookies: &[CookieItem]) -> String {
     cookies.iter()
         .map(cookie_to_string)
@@ -659,6 +492,9 @@ pub fn cookies_to_header_string(cookies: &[CookieItem]) -> String {
 pub struct SimpleCookie {
     pub key: String,
     pub value: String,
+    /// 仅在值为合成时输出
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub synthetic: bool,
 }
 
 /// 转换为简化的cookie列表
@@ -667,6 +503,65 @@ pub fn to_simple_cookies(cookies: &[CookieItem]) -> Vec<SimpleCookie> {
         SimpleCookie {
             key: c.name.clone(),
             value: c.value.clone(),
+            synthetic: c.is_synthetic(),
         }
     }).collect()
-} 
\ No newline at end of file
+}
+
+/// 用新获取的cookies覆盖同名cookie，其余cookie保持不变
+pub fn merge_cookies(cookies: &mut Vec<CookieItem>, updates: Vec<CookieItem>) {
+    for update in updates {
+        if let Some(existing) = cookies.iter_mut().find(|c| c.name == update.name) {
+            *existing = update;
+        } else {
+            cookies.push(update);
+        }
+    }
+}
+
+/// 按名称查找cookie的值
+pub fn find_cookie_value<'a>(cookies: &'a [CookieItem], name: &str) -> Option<&'a str> {
+    cookies.iter()
+        .find(|c| c.name == name)
+        .map(|c| c.value.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CROSS_DOMAIN_URL: &str = "https://passport.biligame.com/x/passport-login/web/crossDomain?\
+        DedeUserID=12345678&DedeUserID__ckMd5=0a1b2c3d4e5f6a7b&Expires=1765440800\
+        &SESSDATA=1a2b3c4d%2C1765440800%2C5e6f7%2A81CjAbCdEf&bili_jct=0123456789abcdef0123456789abcdef\
+        &gourl=https%3A%2F%2Fwww.bilibili.com&first_domain=.bilibili.com";
+
+    #[test]
+    fn parses_login_url() {
+        let cases: [(&str, &[(&str, &str)]); 3] = [
+            (CROSS_DOMAIN_URL, &[
+                ("DedeUserID", "12345678"),
+                ("DedeUserID__ckMd5", "0a1b2c3d4e5f6a7b"),
+                ("SESSDATA", "1a2b3c4d%2C1765440800%2C5e6f7%2A81CjAbCdEf"),
+                ("bili_jct", "0123456789abcdef0123456789abcdef"),
+            ]),
+            // 空值跳过
+            ("https://passport.biligame.com/x/passport-login/web/crossDomain?DedeUserID=1&bili_jct=&Expires=1765440800", &[
+                ("DedeUserID", "1"),
+            ]),
+            ("https://passport.biligame.com/x/passport-login/web/crossDomain?gourl=https%3A%2F%2Fwww.bilibili.com", &[]),
+        ];
+        for (url, expected) in cases {
+            let cookies = parse_login_url(url).unwrap();
+            let pairs: Vec<(&str, &str)> = cookies.iter().map(|c| (c.name.as_str(), c.value.as_str())).collect();
+            assert_eq!(pairs, expected, "{}", url);
+        }
+
+        let cookies = parse_login_url(CROSS_DOMAIN_URL).unwrap();
+        assert!(cookies.iter().all(|c| c.expires.unwrap().timestamp() == 1765440800));
+        assert!(cookies.iter().all(|c| c.domain == ".bilibili.com" && c.source == CookieSource::PollUrl));
+        for cookie in &cookies {
+            let is_sessdata = cookie.name == "SESSDATA";
+            assert_eq!((cookie.http_only, cookie.secure), (is_sessdata, is_sessdata), "{}", cookie.name);
+        }
+    }
+}
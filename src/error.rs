@@ -1,5 +1,19 @@
+use std::fmt;
 use thiserror::Error;
 
+/// 缺失的Cookie及其缺失原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingCookie {
+    pub name: String,
+    pub reason: String,
+}
+
+impl fmt::Display for MissingCookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.reason)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum BiliError {
     #[error("网络请求错误: {0}")]
@@ -23,12 +37,27 @@ pub enum BiliError {
     #[error("CSV错误: {0}")]
     CsvError(#[from] csv::Error),
     
+    #[error("SQLite错误: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+    
     #[error("登录失败: {0}")]
     LoginError(String),
     
     #[error("Cookie提取失败: {0}")]
     CookieError(String),
     
+    #[error("缺少必要的Cookie: {}", .0.iter().map(|m| m.to_string()).collect::<Vec<_>>().join("; "))]
+    MissingCookies(Vec<MissingCookie>),
+    
+    #[error("账号配置错误: {0}")]
+    ProfileError(String),
+    
+    #[error("Cookie刷新失败: {0}")]
+    RefreshError(String),
+    
+    #[error("浏览器Cookie数据库错误: {0}")]
+    BrowserError(String),
+    
     #[error("API错误: 状态码 {0}, 消息: {1}")]
     ApiError(i32, String),
 } 
\ No newline at end of file
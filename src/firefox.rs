@@ -0,0 +1,236 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, ErrorCode, TransactionBehavior};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cookies::{CookieItem, SameSite};
+use crate::error::BiliError;
+use crate::jar::is_bilibili_domain;
+use crate::output::reject_non_browser_cookies;
+
+/// Firefox配置文件目录中的Cookie数据库文件名
+pub const COOKIES_DB_FILE_NAME: &str = "cookies.sqlite";
+
+/// 默认 (非容器标签页、非隐私窗口) 的originAttributes
+const DEFAULT_ORIGIN_ATTRIBUTES: &str = "";
+
+/// `schemeMap` 中表示经由HTTPS设置的位
+const SCHEME_HTTPS: i64 = 2;
+
+/// 从该schema版本 (`PRAGMA user_version`，Firefox 136) 起，expiry以毫秒而非秒保存
+const EXPIRY_MILLIS_SCHEMA_VERSION: i64 = 15;
+
+/// 写入Firefox数据库的结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FirefoxWriteSummary {
+    /// 新增或更新的Cookie数量
+    pub written: usize,
+    /// 跳过的会话Cookie数量 (Firefox不会在cookies.sqlite中持久化会话Cookie)
+    pub skipped_session: usize,
+}
+
+/// 将B站的Cookies写入Firefox配置文件的 `cookies.sqlite`
+///
+/// `path` 可以是数据库文件本身，也可以是包含它的配置文件目录。
+/// 按 (name, host, path, originAttributes) 新增或更新 `moz_cookies` 中的行，其他网站的行保持不变。
+/// Firefox运行时会独占数据库，此时返回错误而不做任何修改；含合成值或app端令牌条目时同样拒绝写入。
+pub fn save_to_firefox(cookies: &[CookieItem], path: &Path) -> Result<FirefoxWriteSummary> {
+    reject_non_browser_cookies(cookies, "Firefox")?;
+    let db_path = resolve_db_path(path)?;
+    let mut conn = Connection::open(&db_path)?;
+    // rusqlite默认会等待锁释放5秒，这里要求立即失败
+    conn.busy_timeout(Duration::ZERO)?;
+
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Exclusive)
+        .map_err(locked_error)?;
+    let expiry_in_millis = expiry_in_millis(&tx)?;
+
+    let now = Utc::now();
+    let mut summary = FirefoxWriteSummary::default();
+    {
+        let mut upsert = tx.prepare(
+            "INSERT INTO moz_cookies (originAttributes, name, value, host, path, expiry, lastAccessed, creationTime,
+                isSecure, isHttpOnly, sameSite, rawSameSite, schemeMap)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11, ?12)
+             ON CONFLICT (name, host, path, originAttributes) DO UPDATE SET
+                value = excluded.value, expiry = excluded.expiry, lastAccessed = excluded.lastAccessed,
+                isSecure = excluded.isSecure, isHttpOnly = excluded.isHttpOnly,
+                sameSite = excluded.sameSite, rawSameSite = excluded.rawSameSite, schemeMap = excluded.schemeMap",
+        )?;
+        for cookie in cookies.iter().filter(|c| !c.name.is_empty() && is_bilibili_domain(&c.domain)) {
+            let Some(expires) = cookie.expires else {
+                summary.skipped_session += 1;
+                continue;
+            };
+            let expiry = if expiry_in_millis { expires.timestamp_millis() } else { expires.timestamp() };
+            upsert.execute(params![
+                DEFAULT_ORIGIN_ATTRIBUTES,
+                cookie.name,
+                cookie.value,
+                firefox_host(cookie),
+                cookie.path,
+                expiry,
+                to_micros(cookie.last_access.unwrap_or(now)),
+                to_micros(cookie.creation_time.unwrap_or(now)),
+                cookie.secure,
+                cookie.http_only,
+                same_site_value(cookie.same_site),
+                SCHEME_HTTPS,
+            ])?;
+            summary.written += 1;
+        }
+    }
+    tx.commit()?;
+    Ok(summary)
+}
+
+/// 配置文件目录则补上数据库文件名；数据库需已由Firefox创建
+fn resolve_db_path(path: &Path) -> Result<PathBuf> {
+    let db_path = if path.is_dir() { path.join(COOKIES_DB_FILE_NAME) } else { path.to_path_buf() };
+    if !db_path.is_file() {
+        return Err(BiliError::BrowserError(format!(
+            "找不到 {}，请先用Firefox打开一次该配置文件", db_path.display()
+        )).into());
+    }
+    Ok(db_path)
+}
+
+/// 数据库被占用时给出明确的提示
+fn locked_error(err: rusqlite::Error) -> anyhow::Error {
+    match err.sqlite_error_code() {
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => {
+            BiliError::BrowserError("cookies.sqlite已被锁定，请先关闭Firefox".to_string()).into()
+        }
+        _ => err.into(),
+    }
+}
+
+/// 根据数据库的schema版本判断expiry的单位
+fn expiry_in_millis(conn: &Connection) -> Result<bool> {
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(locked_error)?;
+    if version <= 0 {
+        return Err(BiliError::BrowserError("无法识别cookies.sqlite的版本，请确认它由Firefox创建".to_string()).into());
+    }
+    Ok(version >= EXPIRY_MILLIS_SCHEMA_VERSION)
+}
+
+/// Firefox以前导点区分域Cookie与host-only Cookie
+fn firefox_host(cookie: &CookieItem) -> String {
+    let domain = cookie.domain.trim_start_matches('.');
+    if cookie.host_only {
+        domain.to_string()
+    } else {
+        format!(".{}", domain)
+    }
+}
+
+/// `nsICookie` 的SameSite取值，未指定时与None相同
+fn same_site_value(same_site: Option<SameSite>) -> i64 {
+    match same_site {
+        Some(SameSite::Lax) => 1,
+        Some(SameSite::Strict) => 2,
+        Some(SameSite::None) | None => 0,
+    }
+}
+
+fn to_micros(time: DateTime<Utc>) -> i64 {
+    time.timestamp_micros()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cookies::CookieSource;
+
+    const SCHEMA: &str = "CREATE TABLE moz_cookies (id INTEGER PRIMARY KEY, originAttributes TEXT NOT NULL DEFAULT '',
+        name TEXT, value TEXT, host TEXT, path TEXT, expiry INTEGER, lastAccessed INTEGER, creationTime INTEGER,
+        isSecure INTEGER, isHttpOnly INTEGER, inBrowserElement INTEGER DEFAULT 0, sameSite INTEGER DEFAULT 0,
+        rawSameSite INTEGER DEFAULT 0, schemeMap INTEGER DEFAULT 0, isPartitionedAttributeSet INTEGER DEFAULT 0,
+        CONSTRAINT moz_uniqueid UNIQUE (name, host, path, originAttributes))";
+
+    /// 在临时目录中创建指定schema版本的空数据库
+    fn create_db(name: &str, version: i64) -> (std::path::PathBuf, Connection) {
+        let path = std::env::temp_dir().join(format!("bilicookies-rs-test-{}-{}.sqlite", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn.pragma_update(None, "user_version", version).unwrap();
+        (path, conn)
+    }
+
+    fn cookie(name: &str, value: &str, domain: &str, expires: Option<i64>) -> CookieItem {
+        CookieItem {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            expires: expires.and_then(|ts| DateTime::from_timestamp(ts, 0)),
+            http_only: true,
+            secure: true,
+            same_site: Some(SameSite::Lax),
+            partitioned: false,
+            priority: None,
+            host_only: !domain.starts_with('.'),
+            creation_time: None,
+            last_access: None,
+            source: CookieSource::Imported,
+        }
+    }
+
+    #[test]
+    fn upserts_bilibili_rows_only() {
+        let (path, conn) = create_db("cookies", 14);
+        conn.execute_batch(
+            "INSERT INTO moz_cookies (name, value, host, path, expiry, creationTime) VALUES
+                ('SESSDATA', 'old', '.bilibili.com', '/', 1900000000, 42),
+                ('sid', 'other', '.example.com', '/', 1900000000, 42)",
+        ).unwrap();
+
+        let cookies = [
+            cookie("SESSDATA", "new", ".bilibili.com", Some(1800000000)),
+            cookie("b_lsid", "lsid", "www.bilibili.com", None),
+            cookie("bili_jct", "csrf", ".bilibili.com", Some(1800000000)),
+            cookie("tracker", "x", ".example.com", Some(1800000000)),
+        ];
+        let summary = save_to_firefox(&cookies, &path).unwrap();
+        assert_eq!(summary, FirefoxWriteSummary { written: 2, skipped_session: 1 });
+
+        let rows: Vec<(String, String, String, i64, i64, i64)> = conn
+            .prepare("SELECT name, value, host, expiry, creationTime, sameSite FROM moz_cookies ORDER BY id").unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))).unwrap()
+            .collect::<rusqlite::Result<_>>().unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], ("SESSDATA".into(), "new".into(), ".bilibili.com".into(), 1800000000, 42, 1));
+        assert_eq!(rows[1].1, "other");
+        assert_eq!(rows[2].0, "bili_jct");
+
+        let token = cookie("access_token", "token", ".bilibili.com", Some(1800000000));
+        let err = save_to_firefox(&[cookies[0].clone(), token], &path).unwrap_err();
+        assert!(err.to_string().contains("access_token"), "{}", err);
+
+        conn.execute_batch("BEGIN EXCLUSIVE").unwrap();
+        let err = save_to_firefox(&cookies, &path).unwrap_err();
+        assert!(err.to_string().contains("锁定"), "{}", err);
+        conn.execute_batch("ROLLBACK").unwrap();
+
+        drop(conn);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn picks_expiry_unit_from_schema_version() {
+        let cookies = [cookie("SESSDATA", "abc", ".bilibili.com", Some(1800000000))];
+        for (version, expected) in [(14, 1800000000), (16, 1800000000000)] {
+            let (path, conn) = create_db(&format!("empty-v{}", version), version);
+            save_to_firefox(&cookies, &path).unwrap();
+            let expiry: i64 = conn.query_row("SELECT expiry FROM moz_cookies", [], |row| row.get(0)).unwrap();
+            assert_eq!(expiry, expected, "schema版本 {}", version);
+            drop(conn);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
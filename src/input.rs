@@ -0,0 +1,573 @@
+use anyhow::Result;
+use chrono::DateTime;
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::chromium::read_chromium_cookies;
+use crate::cookies::{CookieItem, CookieSource, SameSite};
+use crate::error::BiliError;
+use crate::jar::is_bilibili_domain;
+use crate::output::{CsvCookieRecord, ExtensionCookie, HTTP_ONLY_PREFIX, SYNTHETIC_MARKER};
+
+/// SQLite数据库文件的文件头
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// 支持的输入格式
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// 根据内容自动识别
+    Auto,
+    /// JSON格式: `--format json` 的输出、`{name, value}` 对象数组、`{"名称": "值"}` 对象或带 `cookies` 数组的对象
+    Json,
+    /// Netscape cookies.txt格式
+    Netscape,
+    /// TOML格式 (即 `--format toml` 的输出)
+    Toml,
+    /// CSV格式 (即 `--format csv` 的输出)
+    Csv,
+    /// 键值对: `k=v; k=v` 形式的Cookie请求头，或每行一个 `k=v` (即 `--format key-value` 的输出)
+    KeyValue,
+    /// EditThisCookie浏览器扩展导出的JSON
+    EditThisCookie,
+    /// Cookie-Editor浏览器扩展导出的JSON
+    CookieEditor,
+    /// Linux版Chromium的Cookies数据库，输入为数据库文件或配置文件目录
+    Chromium,
+}
+
+/// 按指定格式解析Cookies，`Auto` 时先识别格式；旧版本导出的app端令牌条目会被去掉
+pub fn parse_input(content: &str, format: InputFormat) -> Result<Vec<CookieItem>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let cookies = match format {
+        InputFormat::Auto => return parse_input(content, detect_format(content)?),
+        InputFormat::Json => parse_json(content),
+        InputFormat::Netscape => parse_netscape(content),
+        InputFormat::Toml => parse_toml(content),
+        InputFormat::Csv => parse_csv(content),
+        InputFormat::KeyValue => parse_key_value(content),
+        InputFormat::EditThisCookie | InputFormat::CookieEditor => parse_extension_json(content),
+        InputFormat::Chromium => Err(BiliError::CookieError("Chromium的Cookies数据库只能从文件路径读取".to_string()).into()),
+    }?;
+    // 旧版本曾把app端令牌作为Cookie条目导出，读取时去掉
+    non_empty(cookies.into_iter().filter(|c| !c.is_app_token_entry()).collect())
+}
+
+/// 根据内容识别输入格式
+///
+/// 依次检查: Netscape文件头或以制表符分隔的7列、可解析的JSON、含 `name` 和 `value` 列的CSV表头、
+/// 含 `bilicookies_rs_export` 表的TOML，最后是 `k=v` 形式的键值对。
+pub fn detect_format(content: &str) -> Result<InputFormat> {
+    let content = content.trim_start_matches('\u{feff}');
+    let lines = || content.lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty());
+
+    let first_line = lines().next()
+        .ok_or_else(|| BiliError::CookieError("输入为空".to_string()))?;
+    if first_line.contains("HTTP Cookie File")
+        || first_line.starts_with(HTTP_ONLY_PREFIX)
+        || lines().any(|line| !line.starts_with('#') && line.split('\t').count() == 7)
+    {
+        return Ok(InputFormat::Netscape);
+    }
+
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        if let Ok(value) = serde_json::from_str::<Value>(content) {
+            // 浏览器扩展导出的是带 `hostOnly` 的对象数组，EditThisCookie还带有 `id`
+            let first = value.as_array().and_then(|items| items.first()).and_then(Value::as_object);
+            return Ok(match first {
+                Some(object) if object.contains_key("hostOnly") && object.contains_key("id") => InputFormat::EditThisCookie,
+                Some(object) if object.contains_key("hostOnly") => InputFormat::CookieEditor,
+                _ => InputFormat::Json,
+            });
+        }
+    }
+
+    let header: Vec<&str> = first_line.split(',').map(str::trim).collect();
+    if header.contains(&"name") && header.contains(&"value") {
+        return Ok(InputFormat::Csv);
+    }
+
+    if toml::from_str::<toml::Table>(content).is_ok_and(|table| table.contains_key("bilicookies_rs_export")) {
+        return Ok(InputFormat::Toml);
+    }
+
+    if lines().filter(|line| !line.starts_with('#')).any(|line| line.contains('=')) {
+        return Ok(InputFormat::KeyValue);
+    }
+
+    Err(BiliError::CookieError("无法识别输入格式，请使用 --from 指定".to_string()).into())
+}
+
+/// 从文件 (或标准输入) 读取并解析Cookies
+///
+/// 除文本格式外，也接受Chromium的Cookies数据库：`Auto` 时目录及SQLite文件均按Chromium读取。
+pub fn load_input(path: &str, format: InputFormat) -> Result<Vec<CookieItem>> {
+    let is_database = || {
+        let path = Path::new(path);
+        let mut header = [0u8; SQLITE_HEADER.len()];
+        path.is_dir() || fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)).is_ok() && header == SQLITE_HEADER
+    };
+    if format == InputFormat::Chromium || (format == InputFormat::Auto && path != "-" && is_database()) {
+        return read_chromium_cookies(Path::new(path));
+    }
+    parse_input(&read_input(path)?, format)
+}
+
+/// 读取文件内容，路径为 `-` 时读取标准输入
+pub fn read_input(path: &str) -> Result<String> {
+    if path == "-" {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        Ok(content)
+    } else {
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+/// 解析JSON格式的Cookies，只保留B站的Cookie (未给出域名的视为 `.bilibili.com`)
+///
+/// 优先按 `output::format_as_json` 的输出无损读取；否则接受 `{name, value, ...}` 对象数组、
+/// `{"名称": "值"}` 形式的对象，以及带 `cookies` 数组的对象。
+pub fn parse_json(content: &str) -> Result<Vec<CookieItem>> {
+    if let Ok(cookies) = serde_json::from_str::<Vec<CookieItem>>(content) {
+        return non_empty(cookies);
+    }
+
+    let value: Value = serde_json::from_str(content)?;
+    let cookies = match &value {
+        Value::Array(items) => items.iter()
+            .filter_map(|item| item.as_object().and_then(json_object_to_cookie))
+            .collect(),
+        Value::Object(object) => match object.get("cookies").and_then(Value::as_array) {
+            Some(items) => items.iter()
+                .filter_map(|item| item.as_object().and_then(json_object_to_cookie))
+                .collect(),
+            None => object.iter()
+                .filter_map(|(name, value)| value.as_str().map(|value| imported_cookie(name, value)))
+                .collect(),
+        },
+        _ => Vec::new(),
+    };
+    non_empty(cookies)
+}
+
+/// 解析EditThisCookie / Cookie-Editor导出的JSON，只保留B站的Cookie
+pub fn parse_extension_json(content: &str) -> Result<Vec<CookieItem>> {
+    let cookies: Vec<ExtensionCookie> = serde_json::from_str(content)?;
+    non_empty(cookies.into_iter().map(CookieItem::from).collect())
+}
+
+/// 将其他工具导出的 `{name, value, domain, ...}` 对象转换为CookieItem，字段名兼容驼峰和下划线两种写法
+fn json_object_to_cookie(object: &serde_json::Map<String, Value>) -> Option<CookieItem> {
+    let field = |names: &[&str]| names.iter().find_map(|name| object.get(*name));
+    let flag = |names: &[&str]| field(names).and_then(Value::as_bool).unwrap_or(false);
+
+    let name = field(&["name", "key"])?.as_str()?;
+    let value = field(&["value"]).and_then(Value::as_str).unwrap_or_default();
+    let mut cookie = imported_cookie(name, value);
+    if let Some(domain) = field(&["domain"]).and_then(Value::as_str) {
+        cookie.host_only = !domain.starts_with('.');
+        cookie.domain = domain.to_string();
+    }
+    if let Some(path) = field(&["path"]).and_then(Value::as_str) {
+        cookie.path = path.to_string();
+    }
+    cookie.expires = field(&["expires", "expirationDate", "expiry"])
+        .and_then(Value::as_f64)
+        .filter(|ts| *ts > 0.0)
+        .and_then(|ts| DateTime::from_timestamp(ts as i64, 0));
+    cookie.http_only = flag(&["http_only", "httpOnly"]);
+    cookie.secure = flag(&["secure"]);
+    cookie.same_site = field(&["same_site", "sameSite"])
+        .and_then(Value::as_str)
+        .and_then(|same_site| match same_site.to_ascii_lowercase().as_str() {
+            "strict" => Some(SameSite::Strict),
+            "lax" => Some(SameSite::Lax),
+            "none" | "no_restriction" => Some(SameSite::None),
+            _ => None,
+        });
+    Some(cookie)
+}
+
+/// 解析键值对形式的Cookies，`;` 和换行均可作为分隔符，可带 `Cookie:` 前缀
+///
+/// `# synthetic:` 注释行之后的第一个Cookie视为合成值。
+pub fn parse_key_value(content: &str) -> Result<Vec<CookieItem>> {
+    let mut cookies = Vec::new();
+    let mut synthetic_next = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('#') {
+            synthetic_next |= line.starts_with(SYNTHETIC_MARKER);
+            continue;
+        }
+        let pairs = line.strip_prefix("Cookie:").unwrap_or(line)
+            .split(';')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .filter(|(name, _)| !name.is_empty());
+        for (name, value) in pairs {
+            let mut cookie = imported_cookie(name, value);
+            if std::mem::take(&mut synthetic_next) {
+                cookie.source = CookieSource::Synthesized;
+            }
+            cookies.push(cookie);
+        }
+    }
+    non_empty(cookies)
+}
+
+/// 只有名称和值的导入Cookie，默认适用于 `.bilibili.com` 下的所有路径
+fn imported_cookie(name: &str, value: &str) -> CookieItem {
+    CookieItem {
+        name: name.to_string(),
+        value: value.to_string(),
+        domain: ".bilibili.com".to_string(),
+        path: "/".to_string(),
+        expires: None,
+        http_only: false,
+        secure: false,
+        same_site: None,
+        partitioned: false,
+        priority: None,
+        host_only: false,
+        creation_time: None,
+        last_access: None,
+        source: CookieSource::Imported,
+    }
+}
+
+/// `output::format_as_toml` 输出的顶层表
+#[derive(Deserialize)]
+struct TomlCookieList {
+    #[serde(default)]
+    bilicookies_rs_export: Vec<CookieItem>,
+}
+
+/// 解析TOML格式的Cookies (即 `output::format_as_toml` 的输出)，只保留B站的Cookie
+pub fn parse_toml(content: &str) -> Result<Vec<CookieItem>> {
+    let list: TomlCookieList = toml::from_str(content)?;
+    non_empty(list.bilicookies_rs_export)
+}
+
+/// 解析CSV格式的Cookies (即 `output::format_as_csv` 的输出，按表头匹配列)，只保留B站的Cookie
+pub fn parse_csv(content: &str) -> Result<Vec<CookieItem>> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let cookies = reader.deserialize::<CsvCookieRecord>()
+        .map(|record| record.map(CookieItem::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    non_empty(cookies)
+}
+
+/// 只保留B站的Cookie，没有时返回错误
+fn non_empty(cookies: Vec<CookieItem>) -> Result<Vec<CookieItem>> {
+    let cookies: Vec<CookieItem> = cookies.into_iter()
+        .filter(|c| is_bilibili_domain(&c.domain))
+        .collect();
+    if cookies.is_empty() {
+        return Err(BiliError::CookieError("输入中没有B站的Cookie".to_string()).into());
+    }
+    Ok(cookies)
+}
+
+/// 解析Netscape cookies.txt格式的Cookies，只保留B站的Cookie
+///
+/// 兼容curl的 `#HttpOnly_` 前缀；过期时间为0的视为会话Cookie；`# synthetic:` 注释行的下一行视为合成值。
+/// 部分工具会把制表符写成空格，字段数不对时按任意空白重新切分；仍无法解析的行会被跳过。
+pub fn parse_netscape(content: &str) -> Result<Vec<CookieItem>> {
+    let mut cookies = Vec::new();
+    let mut malformed_lines = Vec::new();
+    let mut synthetic_next = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with('#') {
+            synthetic_next |= line.starts_with(SYNTHETIC_MARKER);
+            continue;
+        }
+        let synthetic = std::mem::take(&mut synthetic_next);
+        match parse_netscape_line(line, http_only) {
+            Some(mut cookie) if is_bilibili_domain(&cookie.domain) => {
+                if synthetic {
+                    cookie.source = CookieSource::Synthesized;
+                }
+                cookies.push(cookie);
+            }
+            Some(_) => {}
+            None => malformed_lines.push(index + 1),
+        }
+    }
+
+    if cookies.is_empty() {
+        let detail = if malformed_lines.is_empty() {
+            String::new()
+        } else {
+            format!(" (无法解析的行: {:?})", malformed_lines)
+        };
+        return Err(BiliError::CookieError(format!("输入中没有B站的Cookie{}", detail)).into());
+    }
+    Ok(cookies)
+}
+
+/// 解析一行: domain, include subdomains, path, secure, expires, name, value
+fn parse_netscape_line(line: &str, http_only: bool) -> Option<CookieItem> {
+    let mut fields: Vec<&str> = line.splitn(7, '\t').collect();
+    if fields.len() < 6 {
+        fields = line.split_whitespace().collect();
+    }
+    // 值为空时末尾的制表符可能被编辑器去掉
+    if fields.len() == 6 {
+        fields.push("");
+    }
+    if fields.len() != 7 || fields[5].is_empty() {
+        return None;
+    }
+
+    let include_subdomains = parse_flag(fields[1])?;
+    let bare_domain = fields[0].trim_start_matches('.').to_ascii_lowercase();
+    if bare_domain.is_empty() {
+        return None;
+    }
+    // 部分工具以小数形式写出过期时间
+    let expires = fields[4].parse::<i64>().ok()
+        .or_else(|| fields[4].parse::<f64>().ok().map(|ts| ts as i64))?;
+
+    Some(CookieItem {
+        name: fields[5].to_string(),
+        value: fields[6].to_string(),
+        domain: if include_subdomains { format!(".{}", bare_domain) } else { bare_domain },
+        path: fields[2].to_string(),
+        expires: (expires > 0).then(|| DateTime::from_timestamp(expires, 0)).flatten(),
+        http_only,
+        secure: parse_flag(fields[3])?,
+        same_site: None,
+        partitioned: false,
+        priority: None,
+        host_only: !include_subdomains,
+        creation_time: None,
+        last_access: None,
+        source: CookieSource::Imported,
+    })
+}
+
+/// Netscape格式中的 `TRUE`/`FALSE` (不区分大小写)
+fn parse_flag(field: &str) -> Option<bool> {
+    if field.eq_ignore_ascii_case("TRUE") {
+        Some(true)
+    } else if field.eq_ignore_ascii_case("FALSE") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cookies::Priority;
+    use crate::output::{format_cookies, OutputFormat};
+
+    #[test]
+    fn parses_netscape_file() {
+        let content = "# Netscape HTTP Cookie File\r\n\
+            \r\n\
+            #HttpOnly_.bilibili.com\tTRUE\t/\tTRUE\t1765440800\tSESSDATA\tabc%2C123\r\n\
+            .bilibili.com\tTRUE\t/\tFALSE\t1765440800.5\tbili_jct\t0123\r\n\
+            www.bilibili.com  FALSE  /  FALSE  0  b_lsid  1A2B_19\n\
+            .bilibili.com\tTRUE\t/\tFALSE\t0\tempty\t\n\
+            .bilibili.com\tTRUE\t/\tFALSE\t0\tno_tab\n\
+            .example.com\tTRUE\t/\tFALSE\t0\tother\t1\n\
+            .bilibili.com\tMAYBE\t/\tFALSE\t0\tbroken\t1\n";
+        let cookies = parse_netscape(content).unwrap();
+        let names: Vec<&str> = cookies.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["SESSDATA", "bili_jct", "b_lsid", "empty", "no_tab"]);
+
+        assert!(cookies[0].http_only && cookies[0].secure && !cookies[0].host_only);
+        assert_eq!(cookies[0].expires.unwrap().timestamp(), 1765440800);
+        assert_eq!(cookies[1].expires.unwrap().timestamp(), 1765440800);
+        assert_eq!(cookies[2].domain, "www.bilibili.com");
+        assert!(cookies[2].host_only && cookies[2].expires.is_none());
+        assert_eq!(cookies[3].value, "");
+        assert!(cookies.iter().all(|c| c.source == CookieSource::Imported));
+    }
+
+    fn sample_cookies() -> Vec<CookieItem> {
+        let now = DateTime::from_timestamp(1735689600, 123_456_789).unwrap();
+        vec![
+            CookieItem {
+                name: "SESSDATA".to_string(),
+                value: "abc%2C123%2Cx\"y,z".to_string(),
+                domain: ".bilibili.com".to_string(),
+                path: "/".to_string(),
+                expires: DateTime::from_timestamp(1765440800, 0),
+                http_only: true,
+                secure: true,
+                same_site: Some(SameSite::Lax),
+                partitioned: true,
+                priority: Some(Priority::High),
+                host_only: false,
+                creation_time: Some(now),
+                last_access: Some(now),
+                source: CookieSource::SetCookie,
+            },
+            CookieItem {
+                name: "b_lsid".to_string(),
+                value: String::new(),
+                domain: "www.bilibili.com".to_string(),
+                path: "/video".to_string(),
+                expires: None,
+                http_only: false,
+                secure: false,
+                same_site: None,
+                partitioned: false,
+                priority: None,
+                host_only: true,
+                creation_time: None,
+                last_access: None,
+                source: CookieSource::Synthesized,
+            },
+        ]
+    }
+
+    #[test]
+    fn reads_back_own_formats() {
+        let cookies = sample_cookies();
+        let formats = [
+            (OutputFormat::Json, InputFormat::Json),
+            (OutputFormat::Toml, InputFormat::Toml),
+            (OutputFormat::Csv, InputFormat::Csv),
+        ];
+        for (output_format, input_format) in formats {
+            let written = format_cookies(&cookies, output_format).unwrap();
+            assert_eq!(parse_input(&written, input_format).unwrap(), cookies, "{:?}", input_format);
+            assert_eq!(parse_input(&written, InputFormat::Auto).unwrap(), cookies, "{:?}", input_format);
+        }
+    }
+
+    #[test]
+    fn keeps_synthetic_marker() {
+        let cookies = sample_cookies();
+        for format in [OutputFormat::Netscape, OutputFormat::KeyValue] {
+            let read = parse_input(&format_cookies(&cookies, format).unwrap(), InputFormat::Auto).unwrap();
+            let synthetic: Vec<bool> = read.iter().map(CookieItem::is_synthetic).collect();
+            assert_eq!(synthetic, [false, true], "{:?}", format);
+            assert!(format_cookies(&read, OutputFormat::Puppeteer).is_err(), "{:?}", format);
+        }
+
+        let cookies = parse_key_value("SESSDATA=a\n# synthetic: x\nsid=sid_123; bili_jct=0123\n# note\nbuvid3=b\n").unwrap();
+        let synthetic: Vec<(&str, bool)> = cookies.iter().map(|c| (c.name.as_str(), c.is_synthetic())).collect();
+        assert_eq!(synthetic, [("SESSDATA", false), ("sid", true), ("bili_jct", false), ("buvid3", false)]);
+    }
+
+    #[test]
+    fn reads_legacy_csv() {
+        let content = "name,value,domain,path,expires_rfc3339,http_only,secure,synthetic\n\
+            SESSDATA,abc,.bilibili.com,/,2025-12-11T08:13:20+00:00,true,true,false\n\
+            sid,sid_1700000000,.bilibili.com,/,,false,false,true\n";
+        let cookies = parse_csv(content).unwrap();
+        assert_eq!(cookies[0].expires.unwrap().timestamp(), 1765440800);
+        assert_eq!(cookies[0].source, CookieSource::Imported);
+        assert_eq!(cookies[1].source, CookieSource::Synthesized);
+        assert!(format_cookies(&cookies, OutputFormat::Playwright).is_err());
+
+        let json = r#"[{"name": "sid", "value": "sid_1700000000", "domain": ".bilibili.com", "path": "/",
+            "expires": null, "http_only": false, "secure": false, "synthetic": true}]"#;
+        assert_eq!(parse_json(json).unwrap()[0].source, CookieSource::Synthesized);
+    }
+
+    #[test]
+    fn detects_input_format() {
+        let cookies = sample_cookies();
+        let cases = [
+            (format_cookies(&cookies, OutputFormat::Netscape).unwrap(), InputFormat::Netscape),
+            (".bilibili.com\tTRUE\t/\tFALSE\t0\tsid\t1\n".to_string(), InputFormat::Netscape),
+            (format_cookies(&cookies, OutputFormat::Json).unwrap(), InputFormat::Json),
+            (r#"{"SESSDATA": "abc", "bili_jct": "0123"}"#.to_string(), InputFormat::Json),
+            (format_cookies(&cookies, OutputFormat::Csv).unwrap(), InputFormat::Csv),
+            (format_cookies(&cookies, OutputFormat::Toml).unwrap(), InputFormat::Toml),
+            (format_cookies(&cookies, OutputFormat::KeyValue).unwrap(), InputFormat::KeyValue),
+            ("SESSDATA=abc; bili_jct=0123; DedeUserID=1".to_string(), InputFormat::KeyValue),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(detect_format(&content).unwrap(), expected, "{}", content);
+        }
+        assert!(detect_format("not cookies").is_err());
+    }
+
+    #[test]
+    fn parses_loose_json_and_headers() {
+        let from_map = parse_input(r#"{"SESSDATA": "abc", "bili_jct": "0123"}"#, InputFormat::Auto).unwrap();
+        let from_array = parse_input(r#"[{"name": "SESSDATA", "value": "abc"}, {"key": "bili_jct", "value": "0123"}]"#, InputFormat::Auto).unwrap();
+        let from_header = parse_input("Cookie: SESSDATA=abc; bili_jct=0123", InputFormat::Auto).unwrap();
+        for cookies in [from_map, from_array, from_header] {
+            let pairs: Vec<(&str, &str)> = cookies.iter().map(|c| (c.name.as_str(), c.value.as_str())).collect();
+            assert_eq!(pairs, [("SESSDATA", "abc"), ("bili_jct", "0123")]);
+        }
+
+        let with_token = parse_input("SESSDATA=abc; access_token=t; mid=1", InputFormat::Auto).unwrap();
+        assert_eq!(with_token.len(), 1);
+        assert!(parse_input("access_token=t; refresh_token=r", InputFormat::KeyValue).is_err());
+    }
+
+    #[test]
+    fn reads_browser_extension_exports() {
+        let mut cookies = sample_cookies();
+        for cookie in &mut cookies {
+            cookie.creation_time = None;
+            cookie.last_access = None;
+            cookie.partitioned = false;
+            cookie.priority = None;
+            cookie.source = CookieSource::Imported;
+        }
+        let formats = [
+            (OutputFormat::EditThisCookie, InputFormat::EditThisCookie),
+            (OutputFormat::CookieEditor, InputFormat::CookieEditor),
+        ];
+        for (output_format, input_format) in formats {
+            let written = format_cookies(&cookies, output_format).unwrap();
+            assert_eq!(detect_format(&written).unwrap(), input_format);
+            assert_eq!(parse_input(&written, InputFormat::Auto).unwrap(), cookies, "{:?}", input_format);
+        }
+
+        let exported = r#"[{"domain": ".example.com", "hostOnly": false, "name": "a", "value": "1", "session": true, "storeId": null, "sameSite": null},
+            {"domain": "www.bilibili.com", "expirationDate": 1765440800.25, "hostOnly": true, "httpOnly": false, "name": "b_lsid",
+             "path": "/", "sameSite": "unspecified", "secure": false, "session": false, "storeId": "0", "value": "1A2B", "id": 2}]"#;
+        let cookies = parse_input(exported, InputFormat::Auto).unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert!(cookies[0].host_only && cookies[0].same_site.is_none());
+        assert_eq!(cookies[0].expires.unwrap().timestamp(), 1765440800);
+    }
+
+    #[test]
+    fn rejects_file_without_bilibili_cookies() {
+        assert!(parse_netscape("# Netscape HTTP Cookie File\n.example.com\tTRUE\t/\tFALSE\t0\ta\t1\n").is_err());
+    }
+
+    #[test]
+    fn keeps_only_bilibili_cookies() {
+        let mut cookies = sample_cookies();
+        let mut other = cookies[0].clone();
+        other.name = "NID".to_string();
+        other.domain = ".google.com".to_string();
+        cookies.push(other.clone());
+        for format in [OutputFormat::Json, OutputFormat::Toml, OutputFormat::Csv] {
+            let read = parse_input(&format_cookies(&cookies, format).unwrap(), InputFormat::Auto).unwrap();
+            assert_eq!(read, cookies[..2], "{:?}", format);
+            assert!(parse_input(&format_cookies(&[other.clone()], format).unwrap(), InputFormat::Auto).is_err(), "{:?}", format);
+        }
+        let loose = r#"[{"name": "SESSDATA", "value": "abc"}, {"name": "NID", "value": "1", "domain": ".google.com"}]"#;
+        assert_eq!(parse_json(loose).unwrap().len(), 1);
+    }
+}
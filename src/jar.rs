@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use cookie_store::{CookieDomain, CookieStore, RawCookie};
+use reqwest::{header::HeaderValue, Url};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use crate::cookies::CookieItem;
+use crate::set_cookie::parse_set_cookie;
+
+/// 可在请求结束后枚举的Cookie存储
+///
+/// reqwest自带的 `Jar` 只能按URL取出 `Cookie` 请求头，丢失了域名、过期时间等属性。
+/// 这里用 `cookie_store::CookieStore` 实现 `reqwest::cookie::CookieStore`，
+/// 同时记录每个Cookie的完整属性及接收、发送时间，登录完成后即可直接枚举。
+#[derive(Debug, Default)]
+pub struct SharedCookieStore(RwLock<Inner>);
+
+/// (域名, 路径, 名称)，域名与 [`CookieItem::domain`] 的表示一致
+type CookieKey = (String, String, String);
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// 负责按RFC 6265匹配请求应携带的Cookie
+    store: CookieStore,
+    /// 按RFC 6265解析出的完整属性
+    items: BTreeMap<CookieKey, CookieItem>,
+}
+
+impl SharedCookieStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 枚举存储中所有未过期的B站Cookie
+    pub fn bilibili_cookies(&self) -> Vec<CookieItem> {
+        let now = Utc::now();
+        let inner = self.0.read().expect("Cookie存储锁已损坏");
+        inner.items.values()
+            .filter(|cookie| is_bilibili_domain(&cookie.domain))
+            .filter(|cookie| cookie.expires.is_none_or(|expires| expires > now))
+            .cloned()
+            .collect()
+    }
+}
+
+impl reqwest::cookie::CookieStore for SharedCookieStore {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let now = Utc::now();
+        let mut inner = self.0.write().expect("Cookie存储锁已损坏");
+        let mut raw_cookies = Vec::new();
+        for header in cookie_headers.filter_map(|header| header.to_str().ok()) {
+            if let Ok(raw) = RawCookie::parse(header.to_string()) {
+                raw_cookies.push(raw);
+            }
+            if let Some(mut item) = parse_set_cookie(header).and_then(|c| c.into_cookie_item(url, now)) {
+                let key = (item.domain.clone(), item.path.clone(), item.name.clone());
+                // 覆盖已有的同名Cookie时保留其创建时间
+                if let Some(existing) = inner.items.get(&key) {
+                    item.creation_time = existing.creation_time;
+                }
+                inner.items.insert(key, item);
+            }
+        }
+        inner.store.store_response_cookies(raw_cookies.into_iter(), url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let now = Utc::now();
+        let mut inner = self.0.write().expect("Cookie存储锁已损坏");
+        let matched: Vec<(CookieKey, String)> = inner.store.matches(url)
+            .into_iter()
+            .filter_map(|cookie| {
+                let domain = match &cookie.domain {
+                    CookieDomain::HostOnly(host) => host.clone(),
+                    CookieDomain::Suffix(suffix) => format!(".{}", suffix),
+                    CookieDomain::NotPresent | CookieDomain::Empty => return None,
+                };
+                let key = (domain, String::from(cookie.path.as_ref()), cookie.name().to_string());
+                Some((key, format!("{}={}", cookie.name(), cookie.value())))
+            })
+            .collect();
+
+        for (key, _) in &matched {
+            touch(&mut inner.items, key, now);
+        }
+        let header = matched.into_iter()
+            .map(|(_, pair)| pair)
+            .collect::<Vec<_>>()
+            .join("; ");
+        if header.is_empty() {
+            None
+        } else {
+            HeaderValue::from_str(&header).ok()
+        }
+    }
+}
+
+/// 更新Cookie的最近发送时间
+fn touch(items: &mut BTreeMap<CookieKey, CookieItem>, key: &CookieKey, now: DateTime<Utc>) {
+    if let Some(item) = items.get_mut(key) {
+        item.last_access = Some(now);
+    }
+}
+
+/// 是否为B站 (bilibili.com) 的域名
+pub fn is_bilibili_domain(domain: &str) -> bool {
+    let domain = domain.trim_start_matches('.');
+    domain == "bilibili.com" || domain.ends_with(".bilibili.com")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::cookie::CookieStore as _;
+
+    #[test]
+    fn enumerates_cookies_with_attributes() {
+        let store = SharedCookieStore::new();
+        let url = Url::parse("https://passport.bilibili.com/x/passport-login/web/qrcode/poll").unwrap();
+        let headers = [
+            HeaderValue::from_static("SESSDATA=abc%2C123; Path=/; Domain=bilibili.com; Expires=Wed, 01 Jan 2200 00:00:00 GMT; HttpOnly; Secure"),
+            HeaderValue::from_static("sid=xyz; Path=/"),
+        ];
+        store.set_cookies(&mut headers.iter(), &url);
+
+        let www = Url::parse("https://www.bilibili.com/").unwrap();
+        assert_eq!(store.cookies(&www).unwrap(), "SESSDATA=abc%2C123");
+
+        let cookies = store.bilibili_cookies();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].name, "SESSDATA");
+        assert_eq!(cookies[0].domain, ".bilibili.com");
+        assert!(cookies[0].http_only && cookies[0].secure && cookies[0].expires.is_some());
+        assert!(!cookies[0].host_only && cookies[0].last_access.is_some());
+        assert_eq!(cookies[1].domain, "passport.bilibili.com");
+        assert!(cookies[1].host_only && cookies[1].last_access.is_none());
+    }
+}
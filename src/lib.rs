@@ -0,0 +1,52 @@
+//! bilicookies-rs: B站扫码登录并导出Cookies的库
+//!
+//! 命令行工具 `bilicookies-rs` 只是本库的一个使用者。其他 Rust 程序可以直接调用：
+//!
+//! - [`auth::login_with_qrcode`]：扫码登录，通过回调提供二维码URL及扫码状态 ([`auth::LoginEvent`])，返回带有会话的 [`auth::LoginResult`]
+//! - [`cookies::extract_cookies`]：从登录结果中提取 [`cookies::CookieItem`] 列表
+//! - [`output::format_cookies`] 及 `output::format_as_*`：将Cookies格式化为各种输出格式
+//! - [`config::save_cookies`] / [`config::read_config`]：持久化登录信息
+//! - [`refresh::refresh_cookies`]：使用refresh_token刷新Cookies，免去重新扫码
+//! - [`check::check_cookies`]：通过nav接口检查Cookies是否仍然有效
+//! - [`chromium::read_chromium_cookies`]：从Linux版Chromium的Cookies数据库读取B站Cookies
+//! - [`firefox::save_to_firefox`]：将Cookies写入Firefox配置文件的cookies.sqlite
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use bilicookies_rs::{auth::{self, LoginEvent}, cookies, output::{self, OutputFormat}};
+//!
+//! let login_result = auth::login_with_qrcode(|event| {
+//!     if let LoginEvent::QrCode(url) = event {
+//!         println!("请用B站App扫描该URL对应的二维码: {}", url);
+//!     }
+//! }).await?;
+//! let cookies = cookies::extract_cookies(&login_result).await?;
+//! let text = output::format_cookies(&cookies, OutputFormat::Netscape)?;
+//! println!("{}", text);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod api;
+pub mod auth;
+pub mod check;
+pub mod chromium;
+pub mod config;
+pub mod cookies;
+pub mod error;
+pub mod fingerprint;
+pub mod firefox;
+pub mod input;
+pub mod jar;
+pub mod logout;
+pub mod output;
+pub mod refresh;
+pub mod set_cookie;
+pub mod sign;
+pub mod ticket;
+pub mod wbi;
+
+pub use auth::{login_with_qrcode, LoginEvent, LoginResult};
+pub use cookies::{extract_cookies, CookieItem};
+pub use error::BiliError;
+pub use output::OutputFormat;
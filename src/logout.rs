@@ -0,0 +1,57 @@
+use anyhow::Result;
+use reqwest::Client;
+
+use crate::api::{create_client, logout};
+use crate::check::check_cookies;
+use crate::config;
+use crate::cookies::{cookies_to_header_string, find_cookie_value, CookieItem};
+use crate::error::BiliError;
+
+/// 退出登录后的验证结果
+#[derive(Debug, Clone)]
+pub struct LogoutResult {
+    /// 退出后再次请求nav接口确认会话已失效；nav请求本身失败 (无法验证) 时为 `None`
+    pub verified: Option<bool>,
+}
+
+/// 使用Cookies中的bili_jct退出登录，并通过nav接口验证会话已失效
+pub async fn logout_cookies(client: &Client, cookies: &[CookieItem]) -> Result<LogoutResult> {
+    let csrf = find_cookie_value(cookies, "bili_jct")
+        .ok_or_else(|| BiliError::LoginError("缺少bili_jct，无法退出登录".to_string()))?;
+
+    logout(client, &cookies_to_header_string(cookies), csrf).await?;
+
+    // 退出接口已成功，此时验证请求失败不代表会话仍有效，单独报告为无法验证
+    let verified = check_cookies(client, cookies).await.ok().map(|report| !report.is_login);
+    Ok(LogoutResult { verified })
+}
+
+/// 退出配置中某个账号的登录
+///
+/// `remove` 为 `true` 时从配置中删除该账号，否则清空其Cookies和refresh_token并标记为已注销。
+/// 若退出后nav接口仍显示已登录，则不修改账号并返回错误，以便重试；无法验证时仍视为已退出。
+pub async fn logout_stored_profile(profile: Option<&str>, remove: bool) -> Result<LogoutResult> {
+    let mut stored = config::read_config()?;
+    let target = stored.resolve_profile_mut(profile)?;
+    if target.is_revoked() {
+        return Err(BiliError::ProfileError(format!("账号已注销: {}", target.display_name())).into());
+    }
+
+    let client = create_client()?;
+    let result = logout_cookies(&client, &target.cookies).await?;
+    if result.verified == Some(false) {
+        return Err(BiliError::LoginError(
+            "退出后nav接口仍显示已登录，会话可能未失效；账号未做修改，可稍后重试".to_string()
+        ).into());
+    }
+
+    if remove {
+        let uid = target.uid.to_string();
+        stored.remove_profile(&uid)?;
+    } else {
+        target.revoke();
+    }
+    config::save_config(&stored)?;
+
+    Ok(result)
+}
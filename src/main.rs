@@ -1,50 +1,340 @@
 use anyhow::Result;
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand};
 use colored::Colorize;
+use image::{ImageBuffer, Luma};
+use qrcode::QrCode;
 use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
 
-mod api;
-mod auth;
-mod config;
-mod cookies;
-mod error;
-mod output;
+use bilicookies_rs::{api, auth::{self, LoginEvent}, check, config, cookies::{self, ExtractMode}, fingerprint, firefox, input::{self, InputFormat}, logout, output, refresh, ticket, CookieItem, OutputFormat};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "B站扫码登录获取cookies工具")]
 struct Cli {
+    /// 要执行的命令，默认为 login
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// 要使用的账号 (UID或别名)。登录时为新账号设置该别名，其他操作时选择该账号
+    #[arg(short, long, global = true)]
+    profile: Option<String>,
+
     /// 输出格式
-    #[arg(short, long, value_enum, default_value_t = OutputFormat::Netscape)]
+    #[arg(short, long, global = true, value_enum, default_value_t = OutputFormat::Netscape)]
     format: OutputFormat,
 
-    /// 保存到文件
-    #[arg(short, long)]
+    /// 保存到文件，`-` 表示输出到标准输出
+    #[arg(short, long, global = true)]
     output: Option<String>,
+
+    /// 写入Firefox配置文件 (目录或其中的cookies.sqlite)，需先关闭Firefox。未同时指定 --output 时不再保存文件
+    #[arg(long, global = true)]
+    firefox: Option<PathBuf>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
-enum OutputFormat {
-    /// JSON格式
-    Json,
-    /// Netscape cookies.txt格式
-    Netscape,
-    /// 键值对格式
-    KeyValue,
-    /// TOML格式
-    Toml,
-    /// CSV格式
-    Csv,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 扫码登录，保存到配置并导出Cookies
+    Login {
+        /// 宽松模式：缺少的Cookie用合成值代替 (合成值会在输出中标记为synthetic)
+        #[arg(long)]
+        allow_synthetic: bool,
+
+        /// 登录后上传浏览器指纹以激活buvid3，降低下载等接口触发风控 (-352) 的概率
+        #[arg(long)]
+        activate_buvid: bool,
+
+        /// 使用TV端扫码登录，额外获得app端的access_key (保存到账号中)
+        #[arg(long, conflicts_with = "allow_synthetic")]
+        tv: bool,
+
+        /// 将TV登录获得的app端令牌另存为JSON文件 (默认 bilicookies-rs-app-token.json，`-` 表示标准输出)
+        #[arg(long, requires = "tv", num_args = 0..=1, default_missing_value = output::APP_TOKEN_FILE_NAME)]
+        with_access_key: Option<String>,
+    },
+    /// 以指定格式导出已保存的Cookies，无需重新登录
+    Export {
+        /// 同时将TV登录获得的app端令牌另存为JSON文件 (默认 bilicookies-rs-app-token.json，`-` 表示标准输出)
+        #[arg(long, num_args = 0..=1, default_missing_value = output::APP_TOKEN_FILE_NAME)]
+        with_access_key: Option<String>,
+    },
+    /// 检查已保存 (或从文件导入) 的Cookies是否仍然有效，无效时以非零状态码退出
+    Check {
+        /// 检查该文件中的Cookies而非已保存的账号，`-` 表示标准输入
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// 输入文件的格式，默认根据内容自动识别
+        #[arg(long, value_enum, default_value_t = InputFormat::Auto, requires = "input")]
+        from: InputFormat,
+
+        /// 额外以WBI签名请求空间信息接口，验证Cookies能否通过WBI接口
+        #[arg(long)]
+        wbi: bool,
+    },
+    /// 使用保存的refresh_token刷新Cookies并导出
+    Refresh {
+        /// 即使服务器认为无需刷新也强制刷新
+        #[arg(long)]
+        force: bool,
+    },
+    /// 退出登录使已保存的Cookies失效，并将该账号标记为已退出
+    Logout {
+        /// 退出后从配置中删除该账号，而不是标记为已退出
+        #[arg(long)]
+        remove: bool,
+    },
+    /// 将Cookie文件转换为其他格式，无需登录
+    Convert {
+        /// 输入文件路径，`-` 表示标准输入
+        input: String,
+
+        /// 输入文件的格式，默认根据内容自动识别
+        #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+        from: InputFormat,
+    },
+    /// 导入Cookie文件 (如浏览器扩展导出的cookies.txt)，验证后保存为账号并导出
+    Import {
+        /// 输入文件路径，`-` 表示标准输入
+        input: String,
+
+        /// 输入文件的格式，默认根据内容自动识别
+        #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+        from: InputFormat,
+
+        /// 浏览器localStorage中的 `ac_time_value`，提供后可使用 refresh 刷新导入的Cookies
+        #[arg(long)]
+        refresh_token: Option<String>,
+
+        /// 合并到账号已保存的Cookies中 (同名的以导入的为准)，而不是整体替换
+        #[arg(long)]
+        merge: bool,
+    },
+    /// 管理已保存的账号
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum ProfileCommand {
+    /// 列出所有账号
+    List,
+    /// 显示账号详情 (默认为 --profile 指定的账号或默认账号)
+    Show,
+    /// 为账号设置别名
+    Rename {
+        /// 新的别名
+        alias: String,
+    },
+    /// 删除账号
+    Remove,
+    /// 将账号设为默认账号
+    Default,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let profile = cli.profile.as_deref();
+    let command = cli.command.unwrap_or(Command::Login {
+        allow_synthetic: false,
+        activate_buvid: false,
+        tv: false,
+        with_access_key: None,
+    });
+    
+    let token_path = match &command {
+        Command::Login { with_access_key, .. } | Command::Export { with_access_key } => with_access_key.as_deref(),
+        _ => None,
+    };
+    if token_path == Some("-") && cli.output.as_deref() == Some("-") {
+        anyhow::bail!("app端令牌与Cookies不能同时输出到标准输出，请为其中之一指定文件");
+    }
+    
+    let cookies = match command {
+        Command::Login { tv: true, with_access_key, .. } => tv_login(profile, with_access_key.as_deref()).await?,
+        Command::Login { allow_synthetic, activate_buvid, .. } => {
+            let mode = if allow_synthetic { ExtractMode::Lenient } else { ExtractMode::Strict };
+            login_and_extract(mode, activate_buvid, profile).await?
+        }
+        Command::Export { with_access_key } => export_saved_cookies(profile, with_access_key.as_deref())?,
+        Command::Refresh { force } => refresh_saved_cookies(profile, force).await?,
+        Command::Convert { input, from } => input::load_input(&input, from)?,
+        Command::Import { input, from, refresh_token, merge } => {
+            import_cookies(&input, from, refresh_token.as_deref(), merge, profile).await?
+        }
+        Command::Check { input, from, wbi } => return run_check(profile, input.as_deref(), from, wbi).await,
+        Command::Logout { remove } => return run_logout(profile, remove).await,
+        Command::Profile(command) => return run_profile_command(command, profile),
+    };
+    
+    if let Some(firefox_path) = &cli.firefox {
+        let summary = firefox::save_to_firefox(&cookies, firefox_path)?;
+        eprintln!("\n{} {} ({}个)", "Cookies已写入Firefox:".green(), firefox_path.display(), summary.written);
+        if summary.skipped_session > 0 {
+            eprintln!("{} {}个", "⚠ Firefox不保存会话Cookie，已跳过:".yellow(), summary.skipped_session);
+        }
+    }
+    if cli.firefox.is_none() || cli.output.is_some() {
+        write_output(&cookies, cli.format, cli.output.as_deref())?;
+    }
+    
+    eprintln!("{}", "操作完成!".green().bold());
+    Ok(())
+}
+
+/// 按指定格式输出Cookies到文件，未指定路径时保存到当前目录下的默认文件名，路径为 `-` 时输出到标准输出
+fn write_output(cookies: &[CookieItem], output_format_to_use: OutputFormat, output_path: Option<&str>) -> Result<()> {
+    let formatted_output = output::format_cookies(cookies, output_format_to_use)?;
+    
+    if output_path == Some("-") {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(formatted_output.as_bytes())?;
+        if !formatted_output.ends_with('\n') {
+            stdout.write_all(b"\n")?;
+        }
+    } else if let Some(output_path_str) = output_path {
+        output::save_to_file(&formatted_output, output_path_str)?;
+        eprintln!("\n{} {}", "Cookies已保存到:".green(), output_path_str);
+    } else {
+        // Default file output logic
+        let current_dir = std::env::current_dir()?;
+        let default_output_path = current_dir.join(output_format_to_use.default_file_name());
+        
+        // Ensure parent directory exists (though current_dir usually does)
+        if let Some(parent) = default_output_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        
+        fs::write(&default_output_path, &formatted_output)?;
+        eprintln!(
+            "\n{} {} (格式: {:?})", 
+            "Cookies已默认保存到:".green(), 
+            default_output_path.display(),
+            output_format_to_use
+        );
+    }
+    
+    Ok(())
+}
+
+/// 将app端令牌保存为JSON文件，路径为 `-` 时输出到标准输出
+fn write_app_token(token: &config::AppTokenExport, output_path: &str) -> Result<()> {
+    let content = serde_json::to_string_pretty(token)?;
+    if output_path == "-" {
+        println!("{}", content);
+    } else {
+        output::save_to_file(&content, output_path)?;
+        eprintln!("{} {}", "app端令牌已保存到:".green(), output_path);
+    }
+    Ok(())
+}
+
+/// 在终端展示扫码登录的进度
+fn print_login_event(event: LoginEvent) {
+    match event {
+        LoginEvent::QrCode(url) => {
+            if let Err(e) = display_qrcode(&url) {
+                eprintln!("{} {}", "无法生成二维码:".red(), e);
+                eprintln!("请将以下链接转为二维码后扫描: {}", url);
+            }
+        }
+        LoginEvent::Waiting => eprint!("."),
+        LoginEvent::Scanned => eprintln!("\n{}", "已扫描，等待App确认...".yellow()),
+        LoginEvent::Confirmed => {
+            eprintln!("\n{}", "✓ 扫码成功!".green());
+            eprintln!("正在获取用户信息...");
+        }
+        LoginEvent::Unknown(code) => {
+            eprintln!("\n{}", format!("未知轮询状态，代码: {}。将继续尝试...", code).yellow());
+        }
+    }
+}
+
+/// 在终端显示二维码，同时保存为 qrcode.png
+fn display_qrcode(url: &str) -> Result<()> {
+    let code = QrCode::new(url.as_bytes())?;
+
+    // --- 保存二维码到文件 ---
+    let width = code.width();
+    if width == 0 {
+        eprintln!("{}", "无法生成二维码图像：宽度为0".red());
+    } else {
+        let colors = code.to_colors(); // Vec<qrcode::Color>
+        let scale = 6u32;
+        let image_size = (width as u32) * scale;
+        let mut img_buf = ImageBuffer::new(image_size, image_size);
+
+        for y_qr in 0..width {
+            for x_qr in 0..width {
+                let module_index = y_qr * width + x_qr;
+                let pixel_color = match colors[module_index] {
+                    qrcode::Color::Dark => Luma([0u8]),
+                    qrcode::Color::Light => Luma([255u8]),
+                };
+
+                for y_offset in 0..scale {
+                    for x_offset in 0..scale {
+                        img_buf.put_pixel(
+                            (x_qr as u32) * scale + x_offset,
+                            (y_qr as u32) * scale + y_offset,
+                            pixel_color,
+                        );
+                    }
+                }
+            }
+        }
+        match img_buf.save("qrcode.png") {
+            Ok(_) => eprintln!("二维码图片已保存为 qrcode.png, 您也可以扫描此文件。"),
+            Err(e) => eprintln!(
+                "无法保存二维码图片到文件: {}. 请扫描下方终端二维码。",
+                e.to_string().red()
+            ),
+        }
+    }
+    // --- 结束保存二维码到文件 ---
+
+    // --- 终端二维码 ---
+    let terminal_qr_string = code
+        .render::<qrcode::render::unicode::Dense1x2>()
+        .dark_color(qrcode::render::unicode::Dense1x2::Light)
+        .light_color(qrcode::render::unicode::Dense1x2::Dark)
+        .build();
+    eprintln!("\n{}", terminal_qr_string);
+    eprintln!(
+        "{}",
+        "请使用B站手机APP扫描上方二维码或 qrcode.png 文件并确认登录...".yellow()
+    );
+
+    Ok(())
+}
+
+/// 扫码登录并提取Cookies，同时保存到配置
+async fn login_and_extract(mode: ExtractMode, activate_buvid: bool, alias: Option<&str>) -> Result<Vec<CookieItem>> {
+    eprintln!("{}", "欢迎使用B站扫码登录工具!".green().bold());
+    eprintln!("即将生成二维码，请使用B站手机客户端扫描以登录...");
+    let alias = check_profile_alias(alias)?;
+    
+    let login_result = auth::login_with_qrcode(print_login_event).await?;
+    let mut cookies = cookies::extract_cookies_with_mode(&login_result, mode).await?;
     
-    println!("{}", "欢迎使用B站扫码登录工具!".green().bold());
-    println!("即将生成二维码，请使用B站手机客户端扫描以登录...");
+    let bili_ticket = match ticket::ensure_ticket(&login_result.client, &mut cookies, None).await {
+        Ok(bili_ticket) => Some(bili_ticket),
+        Err(e) => {
+            eprintln!("{} {}", "⚠ 获取bili_ticket失败:".yellow(), e);
+            None
+        }
+    };
     
-    let login_result = auth::login_with_qrcode().await?;
-    let cookies = cookies::extract_cookies(&login_result).await?;
+    if activate_buvid {
+        match fingerprint::activate_fingerprint(&login_result.client, &cookies).await {
+            Ok(()) => eprintln!("{}", "✓ buvid3已激活".green()),
+            Err(e) => eprintln!("{} {}", "⚠ buvid3激活失败:".yellow(), e),
+        }
+    }
     
     // ---- 临时调试代码 开始 ----
     // println!("\nDEBUG: 全部提取到的Cookies:");
@@ -62,73 +352,302 @@ async fn main() -> Result<()> {
     let has_key_cookies = has_sessdata && has_dedeuserid; // bili_jct is important but sometimes not easily available initially
     
     if has_key_cookies {
-        println!("{}", "✓ 已成功获取Cookie".green().bold());
+        eprintln!("{}", "✓ 已成功获取Cookie".green().bold());
         if let Some(uid_cookie) = cookies.iter().find(|c| c.name == "DedeUserID") {
-            println!("{} {}", "用户ID:".cyan(), uid_cookie.value);
+            eprintln!("{} {}", "用户ID:".cyan(), uid_cookie.value);
         }
         if !login_result.username.is_empty() {
-            println!("{} {}", "用户名:".cyan(), login_result.username);
+            eprintln!("{} {}", "用户名:".cyan(), login_result.username);
         } else {
-            println!("{} {}", "用户名:".cyan(), "未知".yellow());
+            eprintln!("{} {}", "用户名:".cyan(), "未知".yellow());
         }
-        println!("{} {}", "Cookie数量:".cyan(), cookies.len());
-        println!("\n{}", "Cookie (部分):".yellow().bold());
+        eprintln!("{} {}", "Cookie数量:".cyan(), cookies.len());
+        eprintln!("\n{}", "Cookie (部分):".yellow().bold());
         for cookie_name in ["SESSDATA", "DedeUserID", "bili_jct", "sid"] {
             if let Some(cookie) = cookies.iter().find(|c| c.name == cookie_name) {
-                println!("  {}: {}", cookie.name.cyan(), cookie.value);
+                eprintln!("  {}: {}", cookie.name.cyan(), cookie.value);
             }
         }
         if !has_bili_jct {
-            println!("\n{}", "注意: 未能自动获取bili_jct(CSRF令牌)。部分操作可能受限。".yellow());
+            eprintln!("\n{}", "注意: 未能自动获取bili_jct(CSRF令牌)。部分操作可能受限。".yellow());
         }
     } else {
-        println!("{}", "⚠ 警告: 未获取到足够的Cookie (SESSDATA 和 DedeUserID)".yellow().bold());
+        eprintln!("{}", "⚠ 警告: 未获取到足够的Cookie (SESSDATA 和 DedeUserID)".yellow().bold());
     }
     
-    let (output_format_to_use, default_filename_stem, default_extension) = 
-        match cli.format {
-            OutputFormat::Json => (OutputFormat::Json, "bilicookies-rs", "json"),
-            OutputFormat::Netscape => (OutputFormat::Netscape, "bilicookies-rs", "txt"),
-            OutputFormat::KeyValue => (OutputFormat::KeyValue, "bilicookies-rs-kv", "txt"),
-            OutputFormat::Toml => (OutputFormat::Toml, "bilicookies-rs", "toml"),
-            OutputFormat::Csv => (OutputFormat::Csv, "bilicookies-rs", "csv"),
-        };
+    let synthetic_names: Vec<&str> = cookies.iter()
+        .filter(|c| c.is_synthetic())
+        .map(|c| c.name.as_str())
+        .collect();
+    if !synthetic_names.is_empty() {
+        eprintln!(
+            "\n{} {}",
+            "⚠ 以下Cookie为合成值，并非服务器下发:".yellow().bold(),
+            synthetic_names.join(", ")
+        );
+    }
+    
+    config::save_cookies(&cookies, login_result.uid, &login_result.username, &login_result.refresh_token)?;
+    if let Some(bili_ticket) = &bili_ticket {
+        config::save_ticket(login_result.uid, bili_ticket)?;
+    }
+    set_profile_alias(login_result.uid, alias.as_ref())?;
+    eprintln!("{} {}", "账号已保存:".cyan(), login_result.uid);
+    
+    Ok(cookies)
+}
+
+/// TV端扫码登录，保存app端令牌和Cookies，可选将令牌另存为JSON文件
+async fn tv_login(alias: Option<&str>, token_path: Option<&str>) -> Result<Vec<CookieItem>> {
+    eprintln!("{}", "欢迎使用B站扫码登录工具 (TV端)!".green().bold());
+    eprintln!("即将生成二维码，请使用B站手机客户端扫描以登录...");
+    let alias = check_profile_alias(alias)?;
+    
+    let result = auth::login_with_tv_qrcode(print_login_event).await?;
+    config::save_tv_login(&result)?;
+    set_profile_alias(result.uid, alias.as_ref())?;
+    
+    eprintln!("{}", "✓ 已成功获取access_key".green().bold());
+    eprintln!("{} {}", "用户ID:".cyan(), result.uid);
+    eprintln!("{} {}", "用户名:".cyan(), result.username);
+    eprintln!("{} {} 秒", "access_key有效期:".cyan(), result.expires_in);
+    eprintln!("{} {}", "Cookie数量:".cyan(), result.cookies.len());
+    eprintln!("{} {}", "账号已保存:".cyan(), result.uid);
+    
+    if let Some(path) = token_path {
+        write_app_token(&config::AppToken::from_tv_login(&result).to_export(result.uid, chrono::Utc::now()), path)?;
+    }
+    Ok(result.cookies)
+}
+
+/// 为刚登录的账号设置别名
+fn set_profile_alias(uid: u64, alias: Option<&AliasCheck>) -> Result<()> {
+    let Some(check) = alias else {
+        return Ok(());
+    };
+    if let Some(owner) = check.owner.filter(|&owner| owner != uid) {
+        // 登录已经成功，别名冲突时不再报错，以免丢失本次导出
+        eprintln!("{} {} (已被账号 {} 使用)", "⚠ 未设置别名:".yellow(), check.alias, owner);
+        return Ok(());
+    }
+    let mut stored = config::read_config()?;
+    stored.rename_profile(&uid.to_string(), &check.alias)?;
+    config::save_config(&stored)
+}
+
+/// 登录或导入前检查过的别名
+struct AliasCheck {
+    alias: String,
+    /// 检查时已使用该别名的账号
+    owner: Option<u64>,
+}
+
+/// 在扫码或发起网络请求前检查 `--profile` 给出的别名，格式无效时直接报错
+fn check_profile_alias(alias: Option<&str>) -> Result<Option<AliasCheck>> {
+    let Some(alias) = alias else {
+        return Ok(None);
+    };
+    let owner = config::read_config()?.alias_owner(alias)?;
+    Ok(Some(AliasCheck { alias: alias.to_string(), owner }))
+}
 
-    let formatted_output = match output_format_to_use {
-        OutputFormat::Json => output::format_as_json(&cookies)?,
-        OutputFormat::Netscape => output::format_as_netscape(&cookies)?,
-        OutputFormat::KeyValue => output::format_as_key_value(&cookies)?,
-        OutputFormat::Toml => output::format_as_toml(&cookies)?,
-        OutputFormat::Csv => output::format_as_csv(&cookies)?,
+/// 读取已保存的Cookies，可选将app端令牌另存为JSON文件
+fn export_saved_cookies(profile: Option<&str>, token_path: Option<&str>) -> Result<Vec<CookieItem>> {
+    let stored = config::read_config()?;
+    let target = stored.resolve_profile(profile)?;
+    let cookies = target.active_cookies()?.to_vec();
+    
+    if let Some(path) = token_path {
+        let token = target.app_token.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("该账号没有access_key，请使用 login --tv 登录"))?;
+        write_app_token(&token.to_export(target.uid, chrono::Utc::now()), path)?;
+    }
+    Ok(cookies)
+}
+
+/// 刷新配置中保存的Cookies
+async fn refresh_saved_cookies(profile: Option<&str>, force: bool) -> Result<Vec<CookieItem>> {
+    eprintln!("正在检查并刷新已保存的Cookies...");
+    match refresh::refresh_stored_cookies(profile, force).await? {
+        Some(result) => {
+            eprintln!("{}", "✓ Cookies已刷新".green().bold());
+            if let Some(e) = &result.confirm_error {
+                eprintln!("{} {}", "⚠ 刷新未完整完成，新的refresh_token已保存，但旧会话可能尚未失效:".yellow(), e);
+            }
+            Ok(result.cookies)
+        }
+        None => {
+            eprintln!("{}", "Cookies仍然有效，无需刷新".green());
+            Ok(config::read_config()?.resolve_profile(profile)?.cookies.clone())
+        }
+    }
+}
+
+/// 导入Cookie文件，通过nav接口确认已登录并取得UID和用户名后保存为账号
+async fn import_cookies(
+    input_path: &str,
+    format: InputFormat,
+    refresh_token: Option<&str>,
+    merge: bool,
+    alias: Option<&str>,
+) -> Result<Vec<CookieItem>> {
+    let alias = check_profile_alias(alias)?;
+    let cookies = input::load_input(input_path, format)?;
+    let client = api::create_client()?;
+    let report = check::check_cookies(&client, &cookies).await?;
+    let uid = match (report.is_valid(), report.uid) {
+        (true, Some(uid)) => uid,
+        _ => anyhow::bail!("导入的Cookies无效 (code {}: {})，未保存", report.code, report.message),
     };
+    let username = report.uname.unwrap_or_default();
+    
+    config::save_imported_cookies(&cookies, uid, &username, refresh_token, merge)?;
+    set_profile_alias(uid, alias.as_ref())?;
     
-    if let Some(output_path_str) = cli.output {
-        output::save_to_file(&formatted_output, &output_path_str)?;
-        println!("\n{} {}", "Cookies已保存到:".green(), output_path_str);
+    eprintln!("{}", "✓ 已导入Cookies".green().bold());
+    eprintln!("{} {}", "用户ID:".cyan(), uid);
+    eprintln!("{} {}", "用户名:".cyan(), username);
+    eprintln!("{} {}", "Cookie数量:".cyan(), cookies.len());
+    if refresh_token.is_none() && !merge {
+        eprintln!("{}", "注意: 未提供refresh_token，无法使用 refresh 刷新这些Cookies".yellow());
+    }
+    eprintln!("{} {}", "账号已保存:".cyan(), uid);
+    Ok(cookies)
+}
+
+/// 检查Cookies是否有效并打印报告，无效时以非零状态码退出
+async fn run_check(profile: Option<&str>, input_path: Option<&str>, format: InputFormat, wbi: bool) -> Result<()> {
+    let cookies = match input_path {
+        Some(path) => input::load_input(path, format)?,
+        None => config::read_config()?.resolve_profile(profile)?.active_cookies()?.to_vec(),
+    };
+    let client = api::create_client()?;
+    let report = check::check_cookies(&client, &cookies).await?;
+    
+    if report.is_valid() {
+        println!("{}", "✓ Cookies有效".green().bold());
     } else {
-        // Default file output logic
-        let current_dir = std::env::current_dir()?;
-        let filename = format!("{}.{}", default_filename_stem, default_extension);
-        let default_output_path = current_dir.join(filename);
-        
-        // Ensure parent directory exists (though current_dir usually does)
-        if let Some(parent) = default_output_path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)?;
+        println!("{} (code {}: {})", "✗ Cookies无效".red().bold(), report.code, report.message);
+    }
+    println!("{} {}", "已登录:".cyan(), report.is_login);
+    if let Some(uid) = report.uid {
+        println!("{} {}", "用户ID:".cyan(), uid);
+    }
+    if let Some(uname) = &report.uname {
+        println!("{} {}", "用户名:".cyan(), uname);
+    }
+    println!("{} {}", "DedeUserID一致:".cyan(), describe_match(report.uid_matches));
+    println!("{} {}", "bili_jct与csrf一致:".cyan(), describe_match(report.csrf_matches));
+    
+    let mut wbi_ok = true;
+    if let (true, Some(uid)) = (wbi, report.uid) {
+        let code = check::check_wbi(&client, &cookies, uid).await?;
+        wbi_ok = code == 0;
+        println!("{} {}", "WBI接口:".cyan(), describe_match(Some(wbi_ok)));
+        if !wbi_ok {
+            println!("  {} {}", "接口返回状态码:".yellow(), code);
+        }
+    }
+    
+    println!("\n{}", "Cookie有效期:".yellow().bold());
+    for lifetime in &report.lifetimes {
+        let remaining = match lifetime.remaining {
+            None => "会话Cookie".to_string(),
+            Some(_) if lifetime.is_expired() => "已过期".red().to_string(),
+            Some(r) => format!("剩余 {} 天 {} 小时", r.num_days(), r.num_hours() % 24),
+        };
+        println!("  {}: {}", lifetime.name.cyan(), remaining);
+    }
+    
+    if !report.is_valid() || !wbi_ok {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 将可选的一致性检查结果转换为说明文字
+fn describe_match(matches: Option<bool>) -> String {
+    match matches {
+        Some(true) => "是".green().to_string(),
+        Some(false) => "否".red().to_string(),
+        None => "未知".yellow().to_string(),
+    }
+}
+
+/// 退出登录，并根据 `remove` 删除账号或将其标记为已退出
+async fn run_logout(profile: Option<&str>, remove: bool) -> Result<()> {
+    let result = logout::logout_stored_profile(profile, remove).await?;
+    
+    if remove {
+        println!("{}", "✓ 已退出登录并删除该账号".green().bold());
+    } else {
+        println!("{}", "✓ 已退出登录，账号已标记为已退出".green().bold());
+    }
+    if result.verified.is_none() {
+        println!("{}", "⚠ 警告: 无法请求nav接口，未能验证会话已失效".yellow().bold());
+    }
+    Ok(())
+}
+
+/// 执行账号管理子命令
+fn run_profile_command(command: ProfileCommand, selector: Option<&str>) -> Result<()> {
+    let mut stored = config::read_config()?;
+    
+    match command {
+        ProfileCommand::List => {
+            if stored.profiles.is_empty() {
+                println!("{}", "尚未保存任何账号".yellow());
+            }
+            for profile in stored.profiles.values() {
+                let marker = if stored.default_profile == Some(profile.uid) { "*" } else { " " };
+                let status = if profile.is_revoked() { "已退出".red().to_string() } else { String::new() };
+                println!(
+                    "{} {}  {}  {}  {}",
+                    marker.green().bold(),
+                    profile.display_name().cyan(),
+                    profile.username,
+                    profile.last_login.as_deref().unwrap_or("-"),
+                    status
+                );
             }
         }
-        
-        fs::write(&default_output_path, &formatted_output)?;
-        println!(
-            "\n{} {} (格式: {:?})", 
-            "Cookies已默认保存到:".green(), 
-            default_output_path.display(),
-            output_format_to_use
-        );
-        // Optionally, still print to console if not saved via -o
-        // println!("\n{}\n{}", "完整Cookies:".green(), formatted_output);
+        ProfileCommand::Show => {
+            let profile = stored.resolve_profile(selector)?;
+            println!("{} {}", "用户ID:".cyan(), profile.uid);
+            println!("{} {}", "用户名:".cyan(), profile.username);
+            println!("{} {}", "别名:".cyan(), profile.alias.as_deref().unwrap_or("-"));
+            println!("{} {}", "上次登录:".cyan(), profile.last_login.as_deref().unwrap_or("-"));
+            println!("{} {}", "默认账号:".cyan(), stored.default_profile == Some(profile.uid));
+            if let Some(revoked_at) = &profile.revoked_at {
+                println!("{} {}", "已退出登录:".cyan(), revoked_at.red());
+            }
+            println!("{} {}", "Cookie数量:".cyan(), profile.cookies.len());
+            if let Some(expires_at) = profile.bili_ticket.as_ref().and_then(|t| t.expires_at()) {
+                println!("{} {}", "bili_ticket过期时间:".cyan(), expires_at.with_timezone(&chrono::Local));
+            }
+            if let Some(token) = &profile.app_token {
+                println!("{} {}", "access_key过期时间:".cyan(), token.expires_at.with_timezone(&chrono::Local));
+            }
+        }
+        ProfileCommand::Rename { alias } => {
+            let selector = selector.ok_or_else(|| anyhow::anyhow!("请使用 --profile 指定要重命名的账号"))?;
+            stored.rename_profile(selector, &alias)?;
+            config::save_config(&stored)?;
+            println!("{} {}", "✓ 已设置别名:".green(), alias);
+        }
+        ProfileCommand::Remove => {
+            let selector = selector.ok_or_else(|| anyhow::anyhow!("请使用 --profile 指定要删除的账号"))?;
+            let removed = stored.remove_profile(selector)?;
+            config::save_config(&stored)?;
+            println!("{} {}", "✓ 已删除账号:".green(), removed.display_name());
+        }
+        ProfileCommand::Default => {
+            let selector = selector.ok_or_else(|| anyhow::anyhow!("请使用 --profile 指定默认账号"))?;
+            let uid = stored.set_default_profile(selector)?;
+            config::save_config(&stored)?;
+            println!("{} {}", "✓ 默认账号已设置为:".green(), uid);
+        }
     }
     
-    println!("{}", "操作完成!".green().bold());
     Ok(())
 }
@@ -1,17 +1,121 @@
 use anyhow::Result;
+use clap::ValueEnum;
 use std::fs;
 use std::path::Path;
 use csv::Writer;
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-use crate::cookies::{CookieItem, to_simple_cookies, SimpleCookie};
+use crate::cookies::{CookieItem, CookieSource, Priority, SameSite, to_simple_cookies};
+use crate::error::BiliError;
+
+/// 支持的输出格式
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// JSON格式
+    Json,
+    /// Netscape cookies.txt格式
+    Netscape,
+    /// 键值对格式
+    KeyValue,
+    /// TOML格式
+    Toml,
+    /// CSV格式
+    Csv,
+    /// Playwright的storageState JSON
+    Playwright,
+    /// Puppeteer `page.setCookie` 的参数数组 (JSON)
+    Puppeteer,
+    /// 按需先访问的URL分组的WebDriver Cookie对象 (JSON)，供Selenium使用
+    Selenium,
+    /// 添加Cookie前Selenium需要先访问的URL，每行一个
+    SeleniumDomains,
+    /// EditThisCookie浏览器扩展的JSON
+    EditThisCookie,
+    /// Cookie-Editor浏览器扩展的JSON
+    CookieEditor,
+}
+
+impl OutputFormat {
+    /// 默认输出文件名 (不含目录)
+    pub fn default_file_name(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "bilicookies-rs.json",
+            OutputFormat::Netscape => "bilicookies-rs.txt",
+            OutputFormat::KeyValue => "bilicookies-rs-kv.txt",
+            OutputFormat::Toml => "bilicookies-rs.toml",
+            OutputFormat::Csv => "bilicookies-rs.csv",
+            OutputFormat::Playwright => "bilicookies-rs-playwright.json",
+            OutputFormat::Puppeteer => "bilicookies-rs-puppeteer.json",
+            OutputFormat::Selenium => "bilicookies-rs-selenium.json",
+            OutputFormat::SeleniumDomains => "bilicookies-rs-selenium-urls.txt",
+            OutputFormat::EditThisCookie => "bilicookies-rs-editthiscookie.json",
+            OutputFormat::CookieEditor => "bilicookies-rs-cookie-editor.json",
+        }
+    }
+}
+
+/// 按指定格式输出Cookies
+pub fn format_cookies(cookies: &[CookieItem], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => format_as_json(cookies),
+        OutputFormat::Netscape => format_as_netscape(cookies),
+        OutputFormat::KeyValue => format_as_key_value(cookies),
+        OutputFormat::Toml => format_as_toml(cookies),
+        OutputFormat::Csv => format_as_csv(cookies),
+        OutputFormat::Playwright => format_as_playwright(cookies),
+        OutputFormat::Puppeteer => format_as_puppeteer(cookies),
+        OutputFormat::Selenium => format_as_selenium(cookies),
+        OutputFormat::SeleniumDomains => format_as_selenium_domains(cookies),
+        OutputFormat::EditThisCookie => format_as_edit_this_cookie(cookies),
+        OutputFormat::CookieEditor => format_as_cookie_editor(cookies),
+    }
+}
+
+/// 合成Cookie前附加的注释行 (Netscape和键值对格式)
+const SYNTHETIC_COMMENT: &str = "# synthetic: 下一行的Cookie值由bilicookies-rs合成，并非服务器下发\n";
+
+/// 读取时据此识别 `SYNTHETIC_COMMENT`，见 `input::parse_netscape` 和 `input::parse_key_value`
+pub(crate) const SYNTHETIC_MARKER: &str = "# synthetic:";
+
+/// 写入浏览器 (自动化工具、扩展或Firefox) 的Cookies不能包含合成值和app端令牌条目
+///
+/// 这些格式无法标记合成值，合成的Cookie会被当作真实Cookie使用；app端令牌则会随每个请求发送出去。
+pub(crate) fn reject_non_browser_cookies(cookies: &[CookieItem], format_name: &str) -> Result<()> {
+    let names = |filter: fn(&CookieItem) -> bool| -> Vec<&str> {
+        cookies.iter().filter(|c| filter(c)).map(|c| c.name.as_str()).collect()
+    };
+    let synthetic = names(CookieItem::is_synthetic);
+    if !synthetic.is_empty() {
+        return Err(BiliError::CookieError(format!(
+            "{}格式无法标记合成的Cookie，拒绝导出: {}；请改用json、netscape等格式",
+            format_name,
+            synthetic.join(", ")
+        )).into());
+    }
+    let app_tokens = names(CookieItem::is_app_token_entry);
+    if !app_tokens.is_empty() {
+        return Err(BiliError::CookieError(format!(
+            "app端令牌不是Cookie，拒绝导出到{}: {}",
+            format_name,
+            app_tokens.join(", ")
+        )).into());
+    }
+    Ok(())
+}
 
 /// 以JSON格式输出Cookies
 pub fn format_as_json(cookies: &[CookieItem]) -> Result<String> {
     Ok(serde_json::to_string_pretty(cookies)?)
 }
 
+/// curl用来标记HttpOnly Cookie的域名前缀
+pub const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
 /// 以Netscape格式输出Cookies
+///
+/// 第二列 (include subdomains) 由 `host_only` 决定：host-only Cookie的域名不带开头的 `.` 且该列为 `FALSE`，
+/// 其余Cookie的域名以 `.` 开头且该列为 `TRUE`。HttpOnly Cookie按curl的约定在域名前加 `#HttpOnly_`。
 pub fn format_as_netscape(cookies: &[CookieItem]) -> Result<String> {
     let mut output = String::from("# Netscape HTTP Cookie File\n# https://curl.se/docs/http-cookies.html\n");
     
@@ -21,22 +125,30 @@ pub fn format_as_netscape(cookies: &[CookieItem]) -> Result<String> {
             continue; // 跳过没有名称的cookie项
         }
 
+        let include_subdomains = !cookie.host_only;
         let secure_str = if cookie.secure { "TRUE" } else { "FALSE" };
         let expiry_str = match cookie.expires {
             Some(time) => time.timestamp().to_string(),
             None => "0".to_string(), 
         };
         
-        let domain_str = if cookie.domain.starts_with('.') || cookie.domain.matches('.').count() < 1 {
-            cookie.domain.clone()
+        let bare_domain = cookie.domain.trim_start_matches('.');
+        let domain_str = if include_subdomains {
+            format!(".{}", bare_domain)
         } else {
-            format!(".{}", cookie.domain)
+            bare_domain.to_string()
         };
+        let prefix = if cookie.http_only { HTTP_ONLY_PREFIX } else { "" };
+        
+        if cookie.is_synthetic() {
+            output.push_str(SYNTHETIC_COMMENT);
+        }
         
         let line = format!(
-            "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            "{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            prefix,
             domain_str, 
-            "FALSE",    
+            if include_subdomains { "TRUE" } else { "FALSE" },
             cookie.path,      
             secure_str,       
             expiry_str,       
@@ -57,6 +169,9 @@ pub fn format_as_key_value(cookies: &[CookieItem]) -> Result<String> {
     
     for cookie in simple_cookies {
         if !cookie.key.is_empty() { // 确保key不为空
+            if cookie.synthetic {
+                output.push_str(SYNTHETIC_COMMENT);
+            }
             output.push_str(&format!("{}={}\n", cookie.key, cookie.value));
         }
     }
@@ -64,26 +179,26 @@ pub fn format_as_key_value(cookies: &[CookieItem]) -> Result<String> {
     Ok(output)
 }
 
-/// 为TOML序列化定义的包装结构体
+/// 为TOML序列化定义的包装结构体 (读取时见 `input::parse_toml`)
 #[derive(Serialize)]
 struct TomlCookieListInternalWrapper<'a> {
     // 使用一个不容易与用户toml键冲突的名称
-    bilicookies_rs_export: &'a [SimpleCookie],
+    bilicookies_rs_export: &'a [CookieItem],
 }
 
-/// 以TOML格式输出Cookies
+/// 以TOML格式输出Cookies，保留全部字段
 pub fn format_as_toml(cookies: &[CookieItem]) -> Result<String> {
-    let simple_cookies = to_simple_cookies(cookies)
-        .into_iter()
-        // 只过滤key为空的情况。允许value为空。
-        .filter(|sc| !sc.key.is_empty()) 
-        .collect::<Vec<SimpleCookie>>();
+    let named_cookies = cookies.iter()
+        // 只过滤name为空的情况。允许value为空。
+        .filter(|c| !c.name.is_empty())
+        .cloned()
+        .collect::<Vec<CookieItem>>();
 
-    if simple_cookies.is_empty() {
+    if named_cookies.is_empty() {
         return Ok("# No valid cookies (with non-empty names) to export in TOML format.\n".to_string());
     }
 
-    let wrapper = TomlCookieListInternalWrapper { bilicookies_rs_export: &simple_cookies };
+    let wrapper = TomlCookieListInternalWrapper { bilicookies_rs_export: &named_cookies };
     
     match toml::to_string_pretty(&wrapper) {
         Ok(s) => Ok(s),
@@ -93,37 +208,381 @@ pub fn format_as_toml(cookies: &[CookieItem]) -> Result<String> {
     }
 }
 
+/// CSV中的一行，时间均为RFC 3339格式
+///
+/// 读取时缺少的列取默认值，以兼容只有 `name,value,domain,path,expires_rfc3339,http_only,secure` 等列的旧版导出。
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CsvCookieRecord {
+    name: String,
+    value: String,
+    domain: String,
+    path: String,
+    expires_rfc3339: Option<DateTime<Utc>>,
+    http_only: bool,
+    secure: bool,
+    #[serde(default)]
+    same_site: Option<SameSite>,
+    #[serde(default)]
+    partitioned: bool,
+    #[serde(default)]
+    priority: Option<Priority>,
+    #[serde(default)]
+    host_only: bool,
+    #[serde(default)]
+    creation_time_rfc3339: Option<DateTime<Utc>>,
+    #[serde(default)]
+    last_access_rfc3339: Option<DateTime<Utc>>,
+    #[serde(default)]
+    source: Option<CookieSource>,
+    /// 旧版本的合成标记列，只在读取时使用
+    #[serde(default, skip_serializing)]
+    synthetic: bool,
+}
+
+impl From<&CookieItem> for CsvCookieRecord {
+    fn from(cookie: &CookieItem) -> Self {
+        Self {
+            name: cookie.name.clone(),
+            value: cookie.value.clone(),
+            domain: cookie.domain.clone(),
+            path: cookie.path.clone(),
+            expires_rfc3339: cookie.expires,
+            http_only: cookie.http_only,
+            secure: cookie.secure,
+            same_site: cookie.same_site,
+            partitioned: cookie.partitioned,
+            priority: cookie.priority,
+            host_only: cookie.host_only,
+            creation_time_rfc3339: cookie.creation_time,
+            last_access_rfc3339: cookie.last_access,
+            source: Some(cookie.source),
+            synthetic: false,
+        }
+    }
+}
+
+impl From<CsvCookieRecord> for CookieItem {
+    fn from(record: CsvCookieRecord) -> Self {
+        Self {
+            name: record.name,
+            value: record.value,
+            domain: record.domain,
+            path: record.path,
+            expires: record.expires_rfc3339,
+            http_only: record.http_only,
+            secure: record.secure,
+            same_site: record.same_site,
+            partitioned: record.partitioned,
+            priority: record.priority,
+            host_only: record.host_only,
+            creation_time: record.creation_time_rfc3339,
+            last_access: record.last_access_rfc3339,
+            source: CookieSource::from_legacy(record.source, record.synthetic),
+        }
+    }
+}
+
 /// 以CSV格式输出Cookies
 pub fn format_as_csv(cookies: &[CookieItem]) -> Result<String> {
     let mut writer = Writer::from_writer(vec![]);
-    
-    // 写入CSV头部
-    writer.write_record(&["name", "value", "domain", "path", "expires_rfc3339", "http_only", "secure"])?;
 
     for cookie in cookies {
         if cookie.name.is_empty() { // 跳过没有名称的cookie
             continue;
         }
-        let expires = match cookie.expires {
-            Some(time) => time.to_rfc3339(),
-            None => String::new(),
-        };
-        
-        writer.write_record(&[
-            &cookie.name,
-            &cookie.value,
-            &cookie.domain,
-            &cookie.path,
-            &expires,
-            &cookie.http_only.to_string(),
-            &cookie.secure.to_string(),
-        ])?;
+        writer.serialize(CsvCookieRecord::from(cookie))?;
     }
     
     let csv_bytes = writer.into_inner()?;
     Ok(String::from_utf8(csv_bytes)?)
 }
 
+/// Playwright storageState中的Cookie
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaywrightCookie<'a> {
+    name: &'a str,
+    value: &'a str,
+    domain: &'a str,
+    path: &'a str,
+    /// Unix时间戳 (秒)，会话Cookie为 `-1`
+    expires: i64,
+    http_only: bool,
+    secure: bool,
+    same_site: SameSite,
+}
+
+/// Playwright的storageState，可直接传给 `browser.newContext({ storageState })`
+#[derive(Serialize)]
+struct PlaywrightStorageState<'a> {
+    cookies: Vec<PlaywrightCookie<'a>>,
+    /// localStorage，本工具不涉及
+    origins: Vec<serde_json::Value>,
+}
+
+/// 以Playwright storageState格式输出Cookies
+///
+/// Playwright要求每个Cookie都有 `sameSite`，未知时按浏览器的默认行为取 `Lax`。
+pub fn format_as_playwright(cookies: &[CookieItem]) -> Result<String> {
+    reject_non_browser_cookies(cookies, "Playwright")?;
+    let state = PlaywrightStorageState {
+        cookies: cookies.iter()
+            .filter(|c| !c.name.is_empty())
+            .map(|c| PlaywrightCookie {
+                name: &c.name,
+                value: &c.value,
+                domain: &c.domain,
+                path: &c.path,
+                expires: c.expires.map_or(-1, |e| e.timestamp()),
+                http_only: c.http_only,
+                secure: c.secure,
+                same_site: c.same_site.unwrap_or(SameSite::Lax),
+            })
+            .collect(),
+        origins: Vec::new(),
+    };
+    Ok(serde_json::to_string_pretty(&state)?)
+}
+
+/// Puppeteer `page.setCookie` 接受的Cookie参数，未知的属性不输出
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PuppeteerCookie<'a> {
+    name: &'a str,
+    value: &'a str,
+    domain: &'a str,
+    path: &'a str,
+    /// Unix时间戳 (秒)，会话Cookie不输出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<i64>,
+    http_only: bool,
+    secure: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    same_site: Option<SameSite>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<Priority>,
+}
+
+/// 以Puppeteer `page.setCookie` 参数数组的格式输出Cookies
+pub fn format_as_puppeteer(cookies: &[CookieItem]) -> Result<String> {
+    reject_non_browser_cookies(cookies, "Puppeteer")?;
+    let params: Vec<PuppeteerCookie> = cookies.iter()
+        .filter(|c| !c.name.is_empty())
+        .map(|c| PuppeteerCookie {
+            name: &c.name,
+            value: &c.value,
+            domain: &c.domain,
+            path: &c.path,
+            expires: c.expires.map(|e| e.timestamp()),
+            http_only: c.http_only,
+            secure: c.secure,
+            same_site: c.same_site,
+            priority: c.priority,
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&params)?)
+}
+
+/// WebDriver规范中的Cookie对象
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebDriverCookie<'a> {
+    name: &'a str,
+    value: &'a str,
+    path: &'a str,
+    /// host-only Cookie不输出，由浏览器取当前页面的主机名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain: Option<&'a str>,
+    secure: bool,
+    http_only: bool,
+    /// Unix时间戳 (秒)，会话Cookie不输出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expiry: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    same_site: Option<SameSite>,
+}
+
+/// 需要先访问同一URL才能添加的一组Cookie
+#[derive(Serialize)]
+struct WebDriverCookieGroup<'a> {
+    url: String,
+    cookies: Vec<WebDriverCookie<'a>>,
+}
+
+/// 添加该Cookie前需要访问的URL
+///
+/// WebDriver只能为当前页面所在的域名添加Cookie。host-only Cookie访问其主机本身；
+/// 域名Cookie访问该域名下的主机，如 `.bilibili.com` 对应 `https://www.bilibili.com/`。
+fn visit_url(cookie: &CookieItem) -> String {
+    let domain = cookie.domain.trim_start_matches('.');
+    if cookie.host_only || domain.matches('.').count() > 1 {
+        format!("https://{}/", domain)
+    } else {
+        format!("https://www.{}/", domain)
+    }
+}
+
+/// 按需要先访问的URL分组 (保持Cookie首次出现的顺序)
+fn group_by_visit_url(cookies: &[CookieItem]) -> Vec<(String, Vec<&CookieItem>)> {
+    let mut groups: Vec<(String, Vec<&CookieItem>)> = Vec::new();
+    for cookie in cookies.iter().filter(|c| !c.name.is_empty()) {
+        let url = visit_url(cookie);
+        match groups.iter_mut().find(|(u, _)| *u == url) {
+            Some((_, group)) => group.push(cookie),
+            None => groups.push((url, vec![cookie])),
+        }
+    }
+    groups
+}
+
+/// 以WebDriver Cookie对象输出，按添加前需要访问的URL分组
+pub fn format_as_selenium(cookies: &[CookieItem]) -> Result<String> {
+    reject_non_browser_cookies(cookies, "Selenium")?;
+    let groups: Vec<WebDriverCookieGroup> = group_by_visit_url(cookies)
+        .into_iter()
+        .map(|(url, group)| WebDriverCookieGroup {
+            url,
+            cookies: group.into_iter()
+                .map(|c| WebDriverCookie {
+                    name: &c.name,
+                    value: &c.value,
+                    path: &c.path,
+                    domain: (!c.host_only).then_some(c.domain.as_str()),
+                    secure: c.secure,
+                    http_only: c.http_only,
+                    expiry: c.expires.map(|e| e.timestamp()),
+                    same_site: c.same_site,
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&groups)?)
+}
+
+/// 输出添加Cookie前Selenium需要先访问的URL，每行一个
+pub fn format_as_selenium_domains(cookies: &[CookieItem]) -> Result<String> {
+    Ok(group_by_visit_url(cookies)
+        .into_iter()
+        .map(|(url, _)| url + "\n")
+        .collect())
+}
+
+/// EditThisCookie / Cookie-Editor 导入导出的Cookie，字段与 `chrome.cookies.Cookie` 一致
+///
+/// 两者的区别: EditThisCookie带有从1开始的 `id`，`storeId` 为 `"0"`，未指定的SameSite为 `"unspecified"`；
+/// Cookie-Editor没有 `id`，`storeId` 和未指定的SameSite均为 `null`。
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExtensionCookie {
+    domain: String,
+    /// Unix时间戳 (秒，可带小数)，会话Cookie没有该字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expiration_date: Option<f64>,
+    #[serde(default)]
+    host_only: bool,
+    #[serde(default)]
+    http_only: bool,
+    name: String,
+    #[serde(default = "root_path")]
+    path: String,
+    /// `no_restriction`、`lax`、`strict` 或 `unspecified`
+    #[serde(default)]
+    same_site: Option<String>,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    session: bool,
+    #[serde(default)]
+    store_id: Option<String>,
+    #[serde(default)]
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+}
+
+fn root_path() -> String {
+    "/".to_string()
+}
+
+impl ExtensionCookie {
+    /// 按EditThisCookie (`edit_this_cookie` 为 `true`) 或Cookie-Editor的约定转换
+    fn new(cookie: &CookieItem, index: usize, edit_this_cookie: bool) -> Self {
+        let same_site = match cookie.same_site {
+            Some(SameSite::None) => Some("no_restriction"),
+            Some(SameSite::Lax) => Some("lax"),
+            Some(SameSite::Strict) => Some("strict"),
+            None if edit_this_cookie => Some("unspecified"),
+            None => None,
+        };
+        Self {
+            domain: cookie.domain.clone(),
+            expiration_date: cookie.expires.map(|e| e.timestamp() as f64),
+            host_only: cookie.host_only,
+            http_only: cookie.http_only,
+            name: cookie.name.clone(),
+            path: cookie.path.clone(),
+            same_site: same_site.map(str::to_string),
+            secure: cookie.secure,
+            session: cookie.expires.is_none(),
+            store_id: edit_this_cookie.then(|| "0".to_string()),
+            value: cookie.value.clone(),
+            id: edit_this_cookie.then_some(index + 1),
+        }
+    }
+}
+
+impl From<ExtensionCookie> for CookieItem {
+    fn from(cookie: ExtensionCookie) -> Self {
+        let same_site = match cookie.same_site.as_deref() {
+            Some("no_restriction") => Some(SameSite::None),
+            Some("lax") => Some(SameSite::Lax),
+            Some("strict") => Some(SameSite::Strict),
+            _ => None,
+        };
+        Self {
+            expires: cookie.expiration_date
+                .filter(|_| !cookie.session)
+                .and_then(|ts| DateTime::from_timestamp(ts as i64, 0)),
+            name: cookie.name,
+            value: cookie.value,
+            domain: cookie.domain,
+            path: cookie.path,
+            http_only: cookie.http_only,
+            secure: cookie.secure,
+            same_site,
+            partitioned: false,
+            priority: None,
+            host_only: cookie.host_only,
+            creation_time: None,
+            last_access: None,
+            source: CookieSource::Imported,
+        }
+    }
+}
+
+fn format_as_extension_json(cookies: &[CookieItem], edit_this_cookie: bool) -> Result<String> {
+    reject_non_browser_cookies(cookies, if edit_this_cookie { "EditThisCookie" } else { "Cookie-Editor" })?;
+    let exported: Vec<ExtensionCookie> = cookies.iter()
+        .filter(|c| !c.name.is_empty())
+        .enumerate()
+        .map(|(index, c)| ExtensionCookie::new(c, index, edit_this_cookie))
+        .collect();
+    Ok(serde_json::to_string_pretty(&exported)?)
+}
+
+/// 以EditThisCookie扩展可导入的JSON格式输出Cookies
+pub fn format_as_edit_this_cookie(cookies: &[CookieItem]) -> Result<String> {
+    format_as_extension_json(cookies, true)
+}
+
+/// 以Cookie-Editor扩展可导入的JSON格式输出Cookies
+pub fn format_as_cookie_editor(cookies: &[CookieItem]) -> Result<String> {
+    format_as_extension_json(cookies, false)
+}
+
+/// app端令牌的默认输出文件名
+pub const APP_TOKEN_FILE_NAME: &str = "bilicookies-rs-app-token.json";
+
 /// 保存内容到文件
 pub fn save_to_file(content: &str, path: &str) -> Result<()> {
     // 确保父目录存在
@@ -135,4 +594,117 @@ pub fn save_to_file(content: &str, path: &str) -> Result<()> {
     
     fs::write(path, content)?;
     Ok(())
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cookies::CookieSource;
+    use crate::input::parse_netscape;
+
+    fn cookie(name: &str, domain: &str, host_only: bool, http_only: bool, expires: Option<i64>) -> CookieItem {
+        CookieItem {
+            name: name.to_string(),
+            value: format!("{}-value", name),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            expires: expires.and_then(|ts| DateTime::from_timestamp(ts, 0)),
+            http_only,
+            secure: http_only,
+            same_site: None,
+            partitioned: false,
+            priority: None,
+            host_only,
+            creation_time: None,
+            last_access: None,
+            source: CookieSource::SetCookie,
+        }
+    }
+
+    #[test]
+    fn formats_for_browser_automation() {
+        let mut session = cookie("SESSDATA", ".bilibili.com", false, true, Some(1765440800));
+        session.same_site = Some(SameSite::None);
+        let cookies = vec![session, cookie("b_lsid", "www.bilibili.com", true, false, None)];
+
+        let state: serde_json::Value = serde_json::from_str(&format_as_playwright(&cookies).unwrap()).unwrap();
+        assert_eq!(state["origins"], serde_json::json!([]));
+        assert_eq!(state["cookies"][0]["expires"], 1765440800);
+        assert_eq!(state["cookies"][0]["httpOnly"], true);
+        assert_eq!(state["cookies"][0]["sameSite"], "None");
+        assert_eq!(state["cookies"][1]["expires"], -1);
+        assert_eq!(state["cookies"][1]["sameSite"], "Lax");
+
+        let params: serde_json::Value = serde_json::from_str(&format_as_puppeteer(&cookies).unwrap()).unwrap();
+        assert_eq!(params[0]["sameSite"], "None");
+        assert_eq!(params[1]["domain"], "www.bilibili.com");
+        assert!(params[1].get("expires").is_none() && params[1].get("sameSite").is_none());
+
+        let mut synthetic = cookie("bili_jct", ".bilibili.com", false, false, None);
+        synthetic.source = CookieSource::Synthesized;
+        let with_synthetic = [cookies[0].clone(), synthetic];
+        let formats = [OutputFormat::Playwright, OutputFormat::Puppeteer, OutputFormat::EditThisCookie, OutputFormat::CookieEditor];
+        for format in formats {
+            let err = format_cookies(&with_synthetic, format).unwrap_err();
+            assert!(err.to_string().contains("bili_jct"), "{}", err);
+        }
+
+        let with_token = [cookies[0].clone(), cookie("access_token", ".bilibili.com", false, false, None)];
+        for format in formats.into_iter().chain([OutputFormat::Selenium]) {
+            let err = format_cookies(&with_token, format).unwrap_err();
+            assert!(err.to_string().contains("access_token"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn groups_selenium_cookies_by_visit_url() {
+        let cookies = vec![
+            cookie("SESSDATA", ".bilibili.com", false, true, Some(1765440800)),
+            cookie("sid", "passport.bilibili.com", true, false, None),
+            cookie("bili_jct", ".bilibili.com", false, false, None),
+            cookie("innersign", ".space.bilibili.com", false, false, None),
+        ];
+
+        assert_eq!(
+            format_as_selenium_domains(&cookies).unwrap(),
+            "https://www.bilibili.com/\nhttps://passport.bilibili.com/\nhttps://space.bilibili.com/\n"
+        );
+
+        let groups: serde_json::Value = serde_json::from_str(&format_as_selenium(&cookies).unwrap()).unwrap();
+        assert_eq!(groups[0]["cookies"].as_array().unwrap().len(), 2);
+        assert_eq!(groups[0]["cookies"][0]["expiry"], 1765440800);
+        assert_eq!(groups[0]["cookies"][0]["domain"], ".bilibili.com");
+        assert!(groups[1]["cookies"][0].get("domain").is_none());
+        assert!(groups[1]["cookies"][0].get("expiry").is_none());
+
+        let mut synthetic = cookies[2].clone();
+        synthetic.source = CookieSource::Synthesized;
+        assert!(format_as_selenium(&[synthetic]).is_err());
+    }
+
+    #[test]
+    fn netscape_round_trip() {
+        let cookies = vec![
+            cookie("SESSDATA", ".bilibili.com", false, true, Some(1765440800)),
+            cookie("bili_jct", "bilibili.com", false, false, Some(1765440800)),
+            cookie("b_lsid", "www.bilibili.com", true, false, None),
+            cookie("sid", "passport.bilibili.com", true, true, None),
+        ];
+        let output = format_as_netscape(&cookies).unwrap();
+        let parsed = parse_netscape(&output).unwrap();
+
+        assert_eq!(parsed.len(), cookies.len());
+        for (cookie, parsed) in cookies.iter().zip(&parsed) {
+            assert_eq!(parsed.host_only, cookie.host_only, "{}", cookie.name);
+            assert_eq!(parsed.domain.starts_with('.'), !cookie.host_only, "{}", cookie.name);
+            assert_eq!(parsed.domain.trim_start_matches('.'), cookie.domain.trim_start_matches('.'));
+            assert_eq!(parsed.path, cookie.path);
+            assert_eq!(parsed.secure, cookie.secure);
+            assert_eq!(parsed.expires, cookie.expires);
+            assert_eq!(parsed.name, cookie.name);
+            assert_eq!(parsed.value, cookie.value);
+            assert_eq!(parsed.http_only, cookie.http_only);
+        }
+        assert!(output.contains("#HttpOnly_.bilibili.com\tTRUE\t/\tTRUE\t1765440800\tSESSDATA\t"));
+        assert!(output.contains("\nwww.bilibili.com\tFALSE\t/\tFALSE\t0\tb_lsid\t"));
+    }
+}
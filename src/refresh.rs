@@ -0,0 +1,146 @@
+use anyhow::Result;
+use reqwest::Client;
+use rsa::{pkcs8::DecodePublicKey, Oaep, RsaPublicKey};
+use sha2::Sha256;
+
+use crate::api::{confirm_refresh, create_client, get_cookie_info, get_correspond_page, refresh_cookie};
+use crate::config;
+use crate::cookies::{cookies_to_header_string, find_cookie_value, merge_cookies, CookieItem};
+use crate::error::BiliError;
+use crate::ticket::ensure_ticket;
+
+/// 用于生成correspondPath的B站公钥
+const CORRESPOND_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDLgd2OAkcGVtoE3ThUREbio0Eg
+Uc/prcajMKXvkCKFCWhJYJcLkcM2DKKcSeFpD/j6Boy538YXnR6VhcuUJOhH2x71
+nzPjfdTcqMz7djHum0qSZA0AyCBDABUqCrfNgCiJ00Ra7GmRj+YCK1NJEuewlb40
+JNrRuoEUXpabUzGB8QIDAQAB
+-----END PUBLIC KEY-----";
+
+/// refresh_csrf 所在元素的起始标签
+const REFRESH_CSRF_TAG: &str = "<div id=\"1-name\">";
+
+/// 刷新成功后的结果
+#[derive(Debug, Clone)]
+pub struct RefreshResult {
+    /// 合并了新Cookie后的完整Cookie列表
+    pub cookies: Vec<CookieItem>,
+    /// 新的refresh_token
+    pub refresh_token: String,
+    /// 刷新未能完整完成时的错误信息: 确认刷新失败，或刷新响应中没有新的Cookie (此时未确认)
+    ///
+    /// 此时刷新本身已经生效 (旧的refresh_token已用掉)，新的Cookies和refresh_token仍需保存，只是旧会话可能尚未失效。
+    pub confirm_error: Option<String>,
+}
+
+/// 检查Cookie是否需要刷新
+pub async fn needs_refresh(client: &Client, cookies: &[CookieItem]) -> Result<bool> {
+    let csrf = find_cookie_value(cookies, "bili_jct").unwrap_or_default();
+    let info = get_cookie_info(client, &cookies_to_header_string(cookies), csrf).await?;
+    Ok(info.refresh)
+}
+
+/// 根据时间戳生成correspondPath (RSA-OAEP/SHA-256 加密 `refresh_{timestamp}` 后的十六进制串)
+pub fn get_correspond_path(timestamp: i64) -> Result<String> {
+    let public_key = RsaPublicKey::from_public_key_pem(CORRESPOND_PUBLIC_KEY)
+        .map_err(|e| BiliError::RefreshError(format!("公钥解析失败: {}", e)))?;
+    let message = format!("refresh_{}", timestamp);
+    let encrypted = public_key
+        .encrypt(&mut rand::thread_rng(), Oaep::new::<Sha256>(), message.as_bytes())
+        .map_err(|e| BiliError::RefreshError(format!("RSA加密失败: {}", e)))?;
+    Ok(hex::encode(encrypted))
+}
+
+/// 从correspond页面中提取refresh_csrf
+pub fn parse_refresh_csrf(html: &str) -> Option<String> {
+    let start = html.find(REFRESH_CSRF_TAG)? + REFRESH_CSRF_TAG.len();
+    let end = html[start..].find("</div>")?;
+    let value = html[start..start + end].trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// 执行完整的Cookie刷新流程
+///
+/// 依次获取服务器时间戳、生成correspondPath、获取refresh_csrf、刷新Cookie，
+/// 最后用新的bili_jct确认刷新以使旧的refresh_token失效。
+/// 刷新接口成功后，确认失败或响应中没有新Cookie都不会丢弃新的refresh_token，而是记录在 [`RefreshResult::confirm_error`] 中。
+pub async fn refresh_cookies(client: &Client, cookies: &[CookieItem], refresh_token: &str) -> Result<RefreshResult> {
+    if refresh_token.is_empty() {
+        return Err(BiliError::RefreshError("缺少refresh_token".to_string()).into());
+    }
+    let csrf = find_cookie_value(cookies, "bili_jct")
+        .ok_or_else(|| BiliError::RefreshError("缺少bili_jct".to_string()))?
+        .to_string();
+    let cookie_header = cookies_to_header_string(cookies);
+
+    // 1. 获取服务器时间戳
+    let info = get_cookie_info(client, &cookie_header, &csrf).await?;
+
+    // 2. 生成correspondPath并获取refresh_csrf
+    let correspond_path = get_correspond_path(info.timestamp)?;
+    let html = get_correspond_page(client, &cookie_header, &correspond_path).await?;
+    let refresh_csrf = parse_refresh_csrf(&html)
+        .ok_or_else(|| BiliError::RefreshError("未能从correspond页面获取refresh_csrf".to_string()))?;
+
+    // 3. 刷新Cookie
+    let (refresh_data, new_cookies) = refresh_cookie(client, &cookie_header, &csrf, &refresh_csrf, refresh_token).await?;
+    if new_cookies.is_empty() {
+        // 服务器已轮换refresh_token，仍需返回新的refresh_token供保存
+        return Ok(RefreshResult {
+            cookies: cookies.to_vec(),
+            refresh_token: refresh_data.refresh_token,
+            confirm_error: Some("刷新响应中没有新的Cookie，未确认刷新".to_string()),
+        });
+    }
+    let mut merged = cookies.to_vec();
+    merge_cookies(&mut merged, new_cookies);
+
+    // 4. 使用新的Cookie确认刷新，使旧的refresh_token失效
+    let new_csrf = find_cookie_value(&merged, "bili_jct").unwrap_or(&csrf).to_string();
+    let confirm_error = confirm_refresh(client, &cookies_to_header_string(&merged), &new_csrf, refresh_token)
+        .await
+        .err()
+        .map(|e| e.to_string());
+
+    Ok(RefreshResult {
+        cookies: merged,
+        refresh_token: refresh_data.refresh_token,
+        confirm_error,
+    })
+}
+
+/// 刷新配置中保存的某个账号的Cookies并写回配置
+///
+/// `profile` 为UID或别名，未指定时使用默认账号。
+/// 若服务器认为无需刷新且未指定 `force`，返回 `Ok(None)`。无论是否刷新，都会续期即将过期的bili_ticket。
+pub async fn refresh_stored_cookies(profile: Option<&str>, force: bool) -> Result<Option<RefreshResult>> {
+    let mut stored = config::read_config()?;
+    let target = stored.resolve_profile_mut(profile)?;
+    if target.active_cookies()?.is_empty() {
+        return Err(BiliError::RefreshError("该账号没有保存的Cookies，请先登录".to_string()).into());
+    }
+    let refresh_token = target.refresh_token.clone().unwrap_or_default();
+
+    let client = create_client()?;
+    let result = if force || needs_refresh(&client, &target.cookies).await? {
+        let result = refresh_cookies(&client, &target.cookies, &refresh_token).await?;
+        target.cookies = result.cookies.clone();
+        target.refresh_token = Some(result.refresh_token.clone());
+        Some(result)
+    } else {
+        None
+    };
+
+    // 顺便续期即将过期的bili_ticket；它并非登录所必需，续期失败不影响Cookie刷新
+    if let Ok(ticket) = ensure_ticket(&client, &mut target.cookies, target.bili_ticket.as_ref()).await {
+        target.bili_ticket = Some(ticket);
+    }
+    let cookies = target.cookies.clone();
+    config::save_config(&stored)?;
+
+    Ok(result.map(|result| RefreshResult { cookies, ..result }))
+}
@@ -0,0 +1,374 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use reqwest::Url;
+
+use crate::cookies::{CookieItem, CookieSource, Priority, SameSite};
+
+/// 按RFC 6265第5.2节解析出的一条 `Set-Cookie`，尚未结合请求URL确定域名和路径
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SetCookie {
+    pub name: String,
+    /// 原样保留的值 (包括引号)，与浏览器存储的一致
+    pub value: String,
+    pub expires: Option<DateTime<Utc>>,
+    /// 同时出现时优先于 `expires`
+    pub max_age: Option<i64>,
+    /// 已去掉开头的 `.` 并转为小写；为 `None` 时为仅限主机 (host-only) 的Cookie
+    pub domain: Option<String>,
+    /// 为 `None` 时使用请求路径的默认路径
+    pub path: Option<String>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<SameSite>,
+    pub partitioned: bool,
+    pub priority: Option<Priority>,
+}
+
+impl SetCookie {
+    /// 结合请求URL按RFC 6265第5.3节转换为CookieItem
+    ///
+    /// `Max-Age` 优先于 `Expires`；未指定 `Domain` 时为host-only Cookie (域名不带开头的 `.`)；
+    /// `Domain` 与请求主机不匹配时服务器无权设置该Cookie，返回 `None`。
+    pub fn into_cookie_item(self, request_url: &Url, now: DateTime<Utc>) -> Option<CookieItem> {
+        let host = request_url.host_str()?.to_ascii_lowercase();
+        let host_only = self.domain.is_none();
+        let domain = match self.domain {
+            Some(domain) if host == domain || host.ends_with(&format!(".{}", domain)) => format!(".{}", domain),
+            Some(_) => return None,
+            None => host,
+        };
+        let expires = match self.max_age {
+            Some(max_age) if max_age <= 0 => Some(DateTime::UNIX_EPOCH),
+            Some(max_age) => Some(now + Duration::seconds(max_age)),
+            None => self.expires,
+        };
+
+        Some(CookieItem {
+            name: self.name,
+            value: self.value,
+            domain,
+            path: self.path.unwrap_or_else(|| default_path(request_url.path())),
+            expires,
+            http_only: self.http_only,
+            secure: self.secure,
+            same_site: self.same_site,
+            partitioned: self.partitioned,
+            priority: self.priority,
+            host_only,
+            creation_time: Some(now),
+            last_access: None,
+            source: CookieSource::SetCookie,
+        })
+    }
+}
+
+/// 解析一条 `Set-Cookie` 头，格式不合法 (缺少 `=` 或名称为空) 时返回 `None`
+///
+/// 无法识别的属性及取值非法的属性会被忽略；同名属性以最后一个为准。
+pub fn parse_set_cookie(header: &str) -> Option<SetCookie> {
+    let (name_value, attributes) = header.split_once(';').unwrap_or((header, ""));
+    let (name, value) = name_value.split_once('=')?;
+    let name = trim_wsp(name);
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut cookie = SetCookie {
+        name: name.to_string(),
+        value: trim_wsp(value).to_string(),
+        ..SetCookie::default()
+    };
+
+    for attribute in attributes.split(';') {
+        let (attr_name, attr_value) = attribute.split_once('=').unwrap_or((attribute, ""));
+        let attr_value = trim_wsp(attr_value);
+        match trim_wsp(attr_name).to_ascii_lowercase().as_str() {
+            "expires" => {
+                if let Some(expires) = parse_cookie_date(attr_value) {
+                    cookie.expires = Some(expires);
+                }
+            }
+            "max-age" => {
+                if let Some(max_age) = parse_max_age(attr_value) {
+                    cookie.max_age = Some(max_age);
+                }
+            }
+            "domain" if !attr_value.is_empty() => {
+                cookie.domain = Some(attr_value.trim_start_matches('.').to_ascii_lowercase());
+            }
+            "path" => {
+                cookie.path = attr_value.starts_with('/').then(|| attr_value.to_string());
+            }
+            "secure" => cookie.secure = true,
+            "httponly" => cookie.http_only = true,
+            "samesite" => {
+                cookie.same_site = match attr_value.to_ascii_lowercase().as_str() {
+                    "strict" => Some(SameSite::Strict),
+                    "lax" => Some(SameSite::Lax),
+                    "none" => Some(SameSite::None),
+                    _ => cookie.same_site,
+                };
+            }
+            "partitioned" => cookie.partitioned = true,
+            "priority" => {
+                cookie.priority = match attr_value.to_ascii_lowercase().as_str() {
+                    "low" => Some(Priority::Low),
+                    "medium" => Some(Priority::Medium),
+                    "high" => Some(Priority::High),
+                    _ => cookie.priority,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    Some(cookie)
+}
+
+/// 去除首尾的空格和制表符
+fn trim_wsp(s: &str) -> &str {
+    s.trim_matches([' ', '\t'])
+}
+
+/// `Max-Age` 必须是可带负号的十进制整数，溢出时取极值
+fn parse_max_age(s: &str) -> Option<i64> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // 超过时间可表示范围的值按约一万年处理
+    let max_age = digits.parse::<i64>().unwrap_or(i64::MAX).min(315_360_000_000);
+    Some(if s.starts_with('-') { -max_age } else { max_age })
+}
+
+/// 请求路径对应的默认Cookie路径 (RFC 6265第5.1.4节)
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(index) if index > 0 && request_path.starts_with('/') => request_path[..index].to_string(),
+        _ => "/".to_string(),
+    }
+}
+
+/// 按RFC 6265第5.1.1节解析Cookie日期
+///
+/// 该算法不依赖固定格式，可同时处理RFC 1123 (`Sun, 06 Nov 1994 08:49:37 GMT`)、
+/// RFC 850 (`Sunday, 06-Nov-94 08:49:37 GMT`) 和asctime (`Sun Nov  6 08:49:37 1994`) 三种格式。
+pub fn parse_cookie_date(s: &str) -> Option<DateTime<Utc>> {
+    let mut time = None;
+    let mut day = None;
+    let mut month = None;
+    let mut year = None;
+
+    for token in s.split(is_date_delimiter).filter(|t| !t.is_empty()) {
+        if time.is_none() {
+            if let Some(hms) = parse_time_token(token) {
+                time = Some(hms);
+                continue;
+            }
+        }
+        if day.is_none() {
+            if let Some(d) = parse_digits_token(token, 1, 2) {
+                day = Some(d);
+                continue;
+            }
+        }
+        if month.is_none() {
+            if let Some(m) = parse_month_token(token) {
+                month = Some(m);
+                continue;
+            }
+        }
+        if year.is_none() {
+            if let Some(y) = parse_digits_token(token, 2, 4) {
+                year = Some(y);
+            }
+        }
+    }
+
+    let (hour, minute, second) = time?;
+    let year = match year? {
+        y @ 70..=99 => y + 1900,
+        y @ 0..=69 => y + 2000,
+        y => y,
+    };
+    if !(1..=31).contains(&day?) || year < 1601 || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    NaiveDate::from_ymd_opt(year as i32, month?, day?)?
+        .and_hms_opt(hour, minute, second)
+        .map(|dt| dt.and_utc())
+}
+
+/// 日期中的分隔符: %x09 / %x20-2F / %x3B-40 / %x5B-60 / %x7B-7E
+fn is_date_delimiter(c: char) -> bool {
+    matches!(c, '\t' | ' '..='/' | ';'..='@' | '['..='`' | '{'..='~')
+}
+
+/// 匹配开头的 `min`..=`max` 位数字，其后只能是结尾或非数字字符
+fn leading_digits(token: &str, min: usize, max: usize) -> Option<(u32, &str)> {
+    let len = token.bytes().take_while(|b| b.is_ascii_digit()).count();
+    if len < min || len > max {
+        return None;
+    }
+    Some((token[..len].parse().ok()?, &token[len..]))
+}
+
+fn parse_digits_token(token: &str, min: usize, max: usize) -> Option<u32> {
+    leading_digits(token, min, max).map(|(value, _)| value)
+}
+
+/// hms-time = time-field ":" time-field ":" time-field
+fn parse_time_token(token: &str) -> Option<(u32, u32, u32)> {
+    let (hour, rest) = leading_digits(token, 1, 2)?;
+    let (minute, rest) = leading_digits(rest.strip_prefix(':')?, 1, 2)?;
+    let (second, _) = leading_digits(rest.strip_prefix(':')?, 1, 2)?;
+    Some((hour, minute, second))
+}
+
+/// 按前三个字母 (不区分大小写) 匹配月份
+fn parse_month_token(token: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    let prefix = token.get(..3)?.to_ascii_lowercase();
+    MONTHS.iter().position(|m| *m == prefix).map(|i| i as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, s).unwrap()
+    }
+
+    #[test]
+    fn parses_cookie_dates() {
+        let cases = [
+            ("Sun, 06 Nov 1994 08:49:37 GMT", Some(utc(1994, 11, 6, 8, 49, 37))),
+            ("Sunday, 06-Nov-94 08:49:37 GMT", Some(utc(1994, 11, 6, 8, 49, 37))),
+            ("Sun Nov  6 08:49:37 1994", Some(utc(1994, 11, 6, 8, 49, 37))),
+            ("Wed, 11-Jun-2025 06:13:20 GMT", Some(utc(2025, 6, 11, 6, 13, 20))),
+            ("Tue, 10 Jun 2025 06:13:20 GMT", Some(utc(2025, 6, 10, 6, 13, 20))),
+            ("Thu, 01 Jan 1970 00:00:00 GMT", Some(utc(1970, 1, 1, 0, 0, 0))),
+            ("01 Jan 30 1:2:3", Some(utc(2030, 1, 1, 1, 2, 3))),
+            ("Sat, 30 Feb 2025 00:00:00 GMT", None),
+            ("Sun, 06 Nov 1994 24:00:00 GMT", None),
+            ("Sun, 06 Nov 1600 08:49:37 GMT", None),
+            ("Sun, 06 Nov 1994", None),
+            ("", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_cookie_date(input), expected, "{:?}", input);
+        }
+    }
+
+    /// 解析结果的期望值: (name, value, domain, path, expires, http_only, secure, same_site, partitioned, priority)
+    type Expected = (
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        Option<DateTime<Utc>>,
+        bool,
+        bool,
+        Option<SameSite>,
+        bool,
+        Option<Priority>,
+    );
+
+    #[test]
+    fn parses_bilibili_set_cookie_headers() {
+        let now = utc(2025, 1, 1, 0, 0, 0);
+        let passport = "https://passport.bilibili.com/x/passport-login/web/qrcode/poll";
+        let cases: [(&str, &str, Option<Expected>); 16] = [
+            (
+                "SESSDATA=6c1f1c2b%2C1765440800%2Cab12c%2A61CjB; Path=/; Domain=bilibili.com; Expires=Thu, 11 Dec 2025 08:13:20 GMT; HttpOnly; Secure",
+                passport,
+                Some(("SESSDATA", "6c1f1c2b%2C1765440800%2Cab12c%2A61CjB", ".bilibili.com", "/", Some(utc(2025, 12, 11, 8, 13, 20)), true, true, None, false, None)),
+            ),
+            (
+                "bili_jct=0123456789abcdef0123456789abcdef; Path=/; Domain=bilibili.com; Expires=Thu, 11 Dec 2025 08:13:20 GMT",
+                passport,
+                Some(("bili_jct", "0123456789abcdef0123456789abcdef", ".bilibili.com", "/", Some(utc(2025, 12, 11, 8, 13, 20)), false, false, None, false, None)),
+            ),
+            (
+                "DedeUserID__ckMd5=1a2b3c4d5e6f7a8b; Path=/; Domain=bilibili.com; Expires=Thu, 11 Dec 2025 08:13:20 GMT",
+                passport,
+                Some(("DedeUserID__ckMd5", "1a2b3c4d5e6f7a8b", ".bilibili.com", "/", Some(utc(2025, 12, 11, 8, 13, 20)), false, false, None, false, None)),
+            ),
+            // 旧式带 `-` 的日期和以 `.` 开头的域名
+            (
+                "buvid3=A1B2C3D4-E5F6-7890-ABCD-EF0123456789infoc; path=/; expires=Fri, 02-Jan-2026 00:00:00 GMT; domain=.bilibili.com",
+                "https://api.bilibili.com/x/frontend/finger/spi",
+                Some(("buvid3", "A1B2C3D4-E5F6-7890-ABCD-EF0123456789infoc", ".bilibili.com", "/", Some(utc(2026, 1, 2, 0, 0, 0)), false, false, None, false, None)),
+            ),
+            // Max-Age优先于Expires，无论先后
+            (
+                "sid=8xk2l3m4; Max-Age=3600; Path=/; Domain=bilibili.com; Expires=Thu, 11 Dec 2025 08:13:20 GMT",
+                passport,
+                Some(("sid", "8xk2l3m4", ".bilibili.com", "/", Some(utc(2025, 1, 1, 1, 0, 0)), false, false, None, false, None)),
+            ),
+            (
+                "b_nut=1735689600; expires=Thu, 11 Dec 2025 08:13:20 GMT; max-age=0; path=/; domain=bilibili.com",
+                "https://www.bilibili.com/",
+                Some(("b_nut", "1735689600", ".bilibili.com", "/", Some(DateTime::UNIX_EPOCH), false, false, None, false, None)),
+            ),
+            // 非法的Max-Age被忽略
+            (
+                "innersign=0; Max-Age=1h; path=/; domain=.bilibili.com",
+                "https://www.bilibili.com/",
+                Some(("innersign", "0", ".bilibili.com", "/", None, false, false, None, false, None)),
+            ),
+            // 未指定Domain: host-only，路径取请求路径的目录
+            (
+                "b_lsid=1A2B3C4D_19400000000",
+                "https://www.bilibili.com/video/BV1xx411c7mD",
+                Some(("b_lsid", "1A2B3C4D_19400000000", "www.bilibili.com", "/video", None, false, false, None, false, None)),
+            ),
+            // 不以 `/` 开头的Path按默认路径处理
+            (
+                "CURRENT_FNVAL=4048; Path=video",
+                "https://www.bilibili.com/video/BV1xx411c7mD",
+                Some(("CURRENT_FNVAL", "4048", "www.bilibili.com", "/video", None, false, false, None, false, None)),
+            ),
+            (
+                "buvid4=ABCDEF12-3456-7890-ABCD-EF0123456789-024-abc%3D%3D; Path=/; Domain=bilibili.com; Expires=Fri, 02 Jan 2026 00:00:00 GMT; SameSite=None; Secure; Partitioned",
+                "https://api.bilibili.com/x/frontend/finger/spi",
+                Some(("buvid4", "ABCDEF12-3456-7890-ABCD-EF0123456789-024-abc%3D%3D", ".bilibili.com", "/", Some(utc(2026, 1, 2, 0, 0, 0)), false, true, Some(SameSite::None), true, None)),
+            ),
+            (
+                "bili_ticket=eyJhbGciOiJIUzI1NiJ9.eyJleHAiOjE3MzU5NDg4MDB9.sig==; Path=/; Domain=BILIBILI.com; SameSite=lax; Priority=HIGH",
+                "https://api.bilibili.com/bapis/bilibili.api.ticket.v1.Ticket/GenWebTicket",
+                Some(("bili_ticket", "eyJhbGciOiJIUzI1NiJ9.eyJleHAiOjE3MzU5NDg4MDB9.sig==", ".bilibili.com", "/", None, false, false, Some(SameSite::Lax), false, Some(Priority::High))),
+            ),
+            // 引号及值中的空格原样保留，首尾空白被去除
+            (
+                " home_feed_column = \"5 4\" ;Path=/; SameSite=Strict",
+                "https://www.bilibili.com/",
+                Some(("home_feed_column", "\"5 4\"", "www.bilibili.com", "/", None, false, false, Some(SameSite::Strict), false, None)),
+            ),
+            // 同名属性以最后一个为准，未知的SameSite被忽略
+            (
+                "theme_style=light; Path=/a; Path=/; SameSite=Lax; SameSite=Bogus; Priority=Low",
+                "https://www.bilibili.com/",
+                Some(("theme_style", "light", "www.bilibili.com", "/", None, false, false, Some(SameSite::Lax), false, Some(Priority::Low))),
+            ),
+            // Domain与请求主机不匹配
+            ("evil=1; Domain=example.com", passport, None),
+            // 缺少 `=` 或名称为空
+            ("SESSDATA; Path=/", passport, None),
+            ("=value; Path=/", passport, None),
+        ];
+
+        for (header, url, expected) in cases {
+            let url = Url::parse(url).unwrap();
+            let actual = parse_set_cookie(header).and_then(|c| c.into_cookie_item(&url, now)).map(|c| {
+                (c.name, c.value, c.domain, c.path, c.expires, c.http_only, c.secure, c.same_site, c.partitioned, c.priority)
+            });
+            let expected = expected.map(|(name, value, domain, path, expires, http_only, secure, same_site, partitioned, priority)| {
+                (name.to_string(), value.to_string(), domain.to_string(), path.to_string(), expires, http_only, secure, same_site, partitioned, priority)
+            });
+            assert_eq!(actual, expected, "{}", header);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use md5::{Digest, Md5};
+
+/// app端接口使用的appkey/appsec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppKey {
+    pub name: &'static str,
+    pub appkey: &'static str,
+    pub appsec: &'static str,
+}
+
+/// 云视听小电视 (TV端)，用于TV扫码登录
+pub const TV: AppKey = AppKey {
+    name: "tv",
+    appkey: "4409e2ce8ffd12b8",
+    appsec: "59b43e04ad6965f34319062b478f83dd",
+};
+
+/// 哔哩哔哩 Android 客户端
+pub const ANDROID: AppKey = AppKey {
+    name: "android",
+    appkey: "1d8b6e7d45233436",
+    appsec: "560c52ccd288fed045859ed18bffd973",
+};
+
+/// 哔哩哔哩 Android 客户端登录接口
+pub const ANDROID_LOGIN: AppKey = AppKey {
+    name: "android-login",
+    appkey: "783bbb7264451d82",
+    appsec: "2653583c8873dea268ab9386918b1d65",
+};
+
+/// 哔哩哔哩 iOS 客户端
+pub const IOS: AppKey = AppKey {
+    name: "ios",
+    appkey: "27eb53fc9058f8c3",
+    appsec: "c2ed53a74eeefe3cf99fbd01d8c9c375",
+};
+
+/// 已知的appkey/appsec
+pub const KNOWN_APP_KEYS: [AppKey; 4] = [TV, ANDROID, ANDROID_LOGIN, IOS];
+
+/// 按名称或appkey查找已知的appkey/appsec
+pub fn find_app_key(name_or_appkey: &str) -> Option<AppKey> {
+    KNOWN_APP_KEYS.iter()
+        .find(|k| k.name == name_or_appkey || k.appkey == name_or_appkey)
+        .copied()
+}
+
+/// 按 `application/x-www-form-urlencoded` 规则编码 (空格为 `+`，保留 `-_.~`)，与服务端校验签名时的编码一致
+pub fn encode_component(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// 将参数编码为查询字符串 (不排序)
+pub fn encode_query(params: &[(String, String)]) -> String {
+    params.iter()
+        .map(|(k, v)| format!("{}={}", encode_component(k), encode_component(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// 加入appkey后按键名排序，返回附加了 `sign` 的参数列表
+///
+/// `sign` 为排序后的查询字符串拼接appsec后的MD5 (小写十六进制)。参数中已有的appkey和sign会被替换。
+pub fn sign_params<K, V>(params: &[(K, V)], key: &AppKey) -> Vec<(String, String)>
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut signed: Vec<(String, String)> = params.iter()
+        .filter(|(k, _)| k.as_ref() != "appkey" && k.as_ref() != "sign")
+        .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
+        .collect();
+    signed.push(("appkey".to_string(), key.appkey.to_string()));
+    signed.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let sign = hex::encode(Md5::digest(format!("{}{}", encode_query(&signed), key.appsec).as_bytes()));
+    signed.push(("sign".to_string(), sign));
+    signed
+}
+
+/// 签名GET请求的参数，返回可直接拼接在URL后的查询字符串
+pub fn sign_query<K, V>(params: &[(K, V)], key: &AppKey) -> String
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    encode_query(&sign_params(params, key))
+}
+
+/// 签名POST表单的参数，返回可直接传给 `RequestBuilder::form` 的参数列表
+pub fn sign_form<K, V>(params: &[(K, V)], key: &AppKey) -> Vec<(String, String)>
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    sign_params(params, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_documented_vector() {
+        let params = [("id", "114514"), ("str", "1919810"), ("test", "いいよ，こいよ")];
+        let query = sign_query(&params, &ANDROID);
+        assert_eq!(
+            query,
+            "appkey=1d8b6e7d45233436&id=114514&str=1919810\
+             &test=%E3%81%84%E3%81%84%E3%82%88%EF%BC%8C%E3%81%93%E3%81%84%E3%82%88\
+             &sign=01479cf20504d865519ac50f33ba3a7d"
+        );
+    }
+
+    #[test]
+    fn sorts_params_and_appends_sign_last() {
+        let form = sign_form(&[("ts", "1700000000"), ("local_id", "0")], &TV);
+        let keys: Vec<&str> = form.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["appkey", "local_id", "ts", "sign"]);
+
+        let expected = hex::encode(Md5::digest(
+            format!("appkey={}&local_id=0&ts=1700000000{}", TV.appkey, TV.appsec).as_bytes(),
+        ));
+        assert_eq!(form[3].1, expected);
+    }
+
+    #[test]
+    fn replaces_existing_appkey_and_sign() {
+        let fresh = sign_form(&[("a", "1")], &TV);
+        let resigned = sign_form(&[("a", "1"), ("appkey", "other"), ("sign", "stale")], &TV);
+        assert_eq!(fresh, resigned);
+    }
+
+    #[test]
+    fn encodes_like_form_urlencoded() {
+        assert_eq!(encode_component("a b*~/"), "a+b%2A~%2F");
+    }
+
+    #[test]
+    fn finds_known_keys_by_name_or_appkey() {
+        assert_eq!(find_app_key("tv"), Some(TV));
+        assert_eq!(find_app_key("1d8b6e7d45233436"), Some(ANDROID));
+        assert_eq!(find_app_key("unknown"), None);
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::api::gen_web_ticket;
+use crate::cookies::{find_cookie_value, merge_cookies, CookieItem, CookieSource};
+use crate::error::BiliError;
+
+/// 计算hexsign使用的固定key
+const TICKET_HMAC_KEY: &[u8] = b"XgwSnGZ1p";
+
+/// 剩余有效期少于该值时续期
+const RENEW_BEFORE_HOURS: i64 = 24;
+
+/// 网页端的bili_ticket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BiliTicket {
+    pub ticket: String,
+    /// 生成时间戳 (秒)
+    pub created_at: i64,
+    /// 有效期 (秒)
+    pub ttl: i64,
+}
+
+impl BiliTicket {
+    /// 过期时间戳 (秒)，即 `bili_ticket_expires` 的值
+    pub fn expires_timestamp(&self) -> i64 {
+        self.created_at + self.ttl
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.expires_timestamp(), 0)
+    }
+
+    /// 是否已过期或即将过期
+    pub fn needs_renewal(&self, now: DateTime<Utc>) -> bool {
+        self.expires_timestamp() - now.timestamp() < Duration::hours(RENEW_BEFORE_HOURS).num_seconds()
+    }
+
+    /// 转换为 `bili_ticket` 和 `bili_ticket_expires` 两个Cookie
+    pub fn to_cookies(&self) -> Vec<CookieItem> {
+        let expires = self.expires_at();
+        [
+            ("bili_ticket", self.ticket.clone()),
+            ("bili_ticket_expires", self.expires_timestamp().to_string()),
+        ]
+        .into_iter()
+        .map(|(name, value)| CookieItem {
+            name: name.to_string(),
+            value,
+            domain: ".bilibili.com".to_string(),
+            path: "/".to_string(),
+            expires,
+            http_only: false,
+            secure: false,
+            same_site: None,
+            partitioned: false,
+            priority: None,
+            host_only: false,
+            creation_time: DateTime::from_timestamp(self.created_at, 0),
+            last_access: None,
+            source: CookieSource::ApiBody,
+        })
+        .collect()
+    }
+}
+
+/// 以固定key对 `ts{ts}` 计算HMAC-SHA256，返回十六进制串
+pub fn hexsign(ts: i64) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(TICKET_HMAC_KEY).expect("HMAC可接受任意长度的key");
+    mac.update(format!("ts{}", ts).as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// 获取新的bili_ticket，`cookies` 中的bili_jct (若有) 作为csrf
+pub async fn fetch_ticket(client: &Client, cookies: &[CookieItem]) -> Result<BiliTicket> {
+    let ts = Utc::now().timestamp();
+    let csrf = find_cookie_value(cookies, "bili_jct").unwrap_or_default();
+    let data = gen_web_ticket(client, ts, &hexsign(ts), csrf).await?;
+    if data.ticket.is_empty() {
+        return Err(BiliError::ApiError(0, "未返回bili_ticket".to_string()).into());
+    }
+
+    Ok(BiliTicket {
+        ticket: data.ticket,
+        created_at: data.created_at,
+        ttl: data.ttl,
+    })
+}
+
+/// 若 `current` 不存在或即将过期则获取新的bili_ticket，并将其写入 `cookies`
+///
+/// 返回当前有效的bili_ticket。
+pub async fn ensure_ticket(
+    client: &Client,
+    cookies: &mut Vec<CookieItem>,
+    current: Option<&BiliTicket>,
+) -> Result<BiliTicket> {
+    let ticket = match current {
+        Some(ticket) if !ticket.needs_renewal(Utc::now()) => ticket.clone(),
+        _ => fetch_ticket(client, cookies).await?,
+    };
+    merge_cookies(cookies, ticket.to_cookies());
+    Ok(ticket)
+}
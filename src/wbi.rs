@@ -0,0 +1,147 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use md5::{Digest, Md5};
+use reqwest::Client;
+use std::sync::Mutex;
+
+use crate::api::get_nav_response;
+use crate::error::BiliError;
+
+/// 由img_key和sub_key生成mixin key时使用的重排表
+const MIXIN_KEY_ENC_TAB: [usize; 64] = [
+    46, 47, 18, 2, 53, 8, 23, 32, 15, 50, 10, 31, 58, 3, 45, 35, 27, 43, 5, 49, 33, 9, 42, 19, 29,
+    28, 14, 39, 12, 38, 41, 13, 37, 48, 7, 16, 24, 55, 40, 61, 26, 17, 0, 1, 60, 51, 30, 4, 22, 25,
+    54, 21, 56, 59, 6, 63, 57, 62, 11, 36, 20, 34, 44, 52,
+];
+
+/// 签名前需要从参数值中去除的字符
+const FILTERED_CHARS: [char; 5] = ['!', '\'', '(', ')', '*'];
+
+/// 已缓存的mixin key，每天 (本地日期变化后) 重新获取
+#[derive(Debug, Clone)]
+struct CachedMixinKey {
+    mixin_key: String,
+    fetched_at: DateTime<Utc>,
+}
+
+static MIXIN_KEY_CACHE: Mutex<Option<CachedMixinKey>> = Mutex::new(None);
+
+/// 取URL中文件名去掉扩展名的部分，如 `.../7cd084941338484aae1ad9425b84077c.png` 得到key
+fn key_from_url(url: &str) -> Option<&str> {
+    let file_name = url.rsplit('/').next()?;
+    let key = file_name.split('.').next()?;
+    (!key.is_empty()).then_some(key)
+}
+
+/// 根据img_key和sub_key生成32位mixin key
+pub fn get_mixin_key(img_key: &str, sub_key: &str) -> String {
+    let raw: Vec<char> = format!("{}{}", img_key, sub_key).chars().collect();
+    MIXIN_KEY_ENC_TAB.iter()
+        .filter_map(|&i| raw.get(i))
+        .take(32)
+        .collect()
+}
+
+/// 按 `encodeURIComponent` 规则编码 (空格为 `%20`)
+fn encode_component(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// 使用指定的mixin key和时间戳 (秒) 签名参数，返回附加了 `wts` 和 `w_rid` 的查询字符串
+pub fn sign_with_key<K, V>(params: &[(K, V)], mixin_key: &str, wts: i64) -> String
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut pairs: Vec<(String, String)> = params.iter()
+        .filter(|(k, _)| k.as_ref() != "wts" && k.as_ref() != "w_rid")
+        .map(|(k, v)| {
+            let value: String = v.as_ref().chars().filter(|c| !FILTERED_CHARS.contains(c)).collect();
+            (k.as_ref().to_string(), value)
+        })
+        .collect();
+    pairs.push(("wts".to_string(), wts.to_string()));
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let query = pairs.iter()
+        .map(|(k, v)| format!("{}={}", encode_component(k), encode_component(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+    let w_rid = hex::encode(Md5::digest(format!("{}{}", query, mixin_key).as_bytes()));
+
+    format!("{}&w_rid={}", query, w_rid)
+}
+
+/// 从nav接口获取img_url/sub_url并计算mixin key (不使用缓存)
+pub async fn fetch_mixin_key(client: &Client, cookie_header: &str) -> Result<String> {
+    let response = get_nav_response(client, cookie_header).await?;
+    let wbi_img = response.data
+        .and_then(|data| data.wbi_img)
+        .ok_or_else(|| BiliError::ApiError(response.code, "nav接口未返回wbi_img".to_string()))?;
+
+    let img_key = key_from_url(&wbi_img.img_url);
+    let sub_key = key_from_url(&wbi_img.sub_url);
+    match (img_key, sub_key) {
+        (Some(img_key), Some(sub_key)) => Ok(get_mixin_key(img_key, sub_key)),
+        _ => Err(BiliError::ApiError(response.code, "无法从wbi_img中解析key".to_string()).into()),
+    }
+}
+
+/// 获取mixin key，优先使用当天的缓存
+pub async fn mixin_key(client: &Client, cookie_header: &str) -> Result<String> {
+    let today = Local::now().date_naive();
+    if let Some(cached) = MIXIN_KEY_CACHE.lock().expect("WBI缓存锁已损坏").as_ref() {
+        if cached.fetched_at.with_timezone(&Local).date_naive() == today {
+            return Ok(cached.mixin_key.clone());
+        }
+    }
+
+    let mixin_key = fetch_mixin_key(client, cookie_header).await?;
+    *MIXIN_KEY_CACHE.lock().expect("WBI缓存锁已损坏") = Some(CachedMixinKey {
+        mixin_key: mixin_key.clone(),
+        fetched_at: Utc::now(),
+    });
+    Ok(mixin_key)
+}
+
+/// 使用 (缓存的) mixin key 和当前时间签名参数，返回附加了 `wts` 和 `w_rid` 的查询字符串
+pub async fn sign_params<K, V>(client: &Client, cookie_header: &str, params: &[(K, V)]) -> Result<String>
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mixin_key = mixin_key(client, cookie_header).await?;
+    Ok(sign_with_key(params, &mixin_key, Utc::now().timestamp()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_documented_vector() {
+        let mixin_key = get_mixin_key("7cd084941338484aae1ad9425b84077c", "4932caff0ff746eab6f01bf08b70ac45");
+        assert_eq!(mixin_key, "ea1db124af3c7062474693fa704f4ff8");
+
+        let params = [("foo", "114"), ("bar", "514"), ("zab", "1919810")];
+        assert_eq!(
+            sign_with_key(&params, &mixin_key, 1702204169),
+            "bar=514&foo=114&wts=1702204169&zab=1919810&w_rid=8f6f2b5b3d485fe1886cec6a0be8c5d4"
+        );
+    }
+
+    #[test]
+    fn extracts_key_from_wbi_url() {
+        assert_eq!(
+            key_from_url("https://i0.hdslb.com/bfs/wbi/7cd084941338484aae1ad9425b84077c.png"),
+            Some("7cd084941338484aae1ad9425b84077c")
+        );
+    }
+}